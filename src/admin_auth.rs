@@ -0,0 +1,57 @@
+use crate::{app_state::AppState, gateway_error::GatewayError};
+use async_trait::async_trait;
+use axum::extract::{FromRequest, RequestParts};
+use hyper::header::AUTHORIZATION;
+
+/// Extractor guarding the admin endpoints, requires `Authorization: Bearer <ADMIN_TOKEN>`
+pub struct AdminAuth;
+
+#[async_trait]
+impl<B> FromRequest<B> for AdminAuth
+where
+    B: Send,
+{
+    type Rejection = GatewayError;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let admin_token = req
+            .extensions()
+            .get::<AppState>()
+            .and_then(|state| state.admin_token.clone())
+            .ok_or_else(|| {
+                GatewayError::Unauthorized("the admin endpoints are disabled".to_string())
+            })?;
+
+        let provided_token = bearer_token(req.headers())
+            .ok_or_else(|| GatewayError::Unauthorized("missing bearer token".to_string()))?;
+
+        if !constant_time_eq(provided_token.as_bytes(), admin_token.as_bytes()) {
+            return Err(GatewayError::Unauthorized(
+                "invalid admin token".to_string(),
+            ));
+        }
+
+        Ok(AdminAuth)
+    }
+}
+
+/// the token of an `Authorization: Bearer <token>` header
+pub fn bearer_token(headers: &hyper::HeaderMap) -> Option<String> {
+    let value = headers.get(AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.trim().split_once(' ')?;
+
+    match scheme.eq_ignore_ascii_case("bearer") && !token.trim().is_empty() {
+        true => Some(token.trim().to_string()),
+        false => None,
+    }
+}
+
+/// compares two secrets without leaking the position of the first difference through timing
+pub fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right.iter())
+            .fold(0_u8, |result, (a, b)| result | (a ^ b))
+            == 0
+}
@@ -0,0 +1,83 @@
+use crate::{
+    admin_auth::AdminAuth,
+    api_key_client::ApiKeyClient,
+    api_key_record::{ApiKeyInput, ApiKeyRecord, IssuedApiKey},
+    app_state::AppState,
+    config_reloader::{reload_config, ConfigReloadResult},
+    gateway_error::GatewayError,
+};
+use axum::{extract::Path, Extension, Json};
+use hyper::StatusCode;
+
+/// reloads the field configuration and the layouts without a restart
+pub async fn config_reload(
+    _: AdminAuth,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Json<ConfigReloadResult>, GatewayError> {
+    let result = reload_config(&app_state).await?;
+
+    Ok(Json(result))
+}
+
+/// creates an api key, the response is the only place the key is shown
+pub async fn api_key_create(
+    _: AdminAuth,
+    Extension(app_state): Extension<AppState>,
+    Json(payload): Json<ApiKeyInput>,
+) -> Result<(StatusCode, Json<IssuedApiKey>), GatewayError> {
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+    let issued_api_key = api_key_client.create_api_key(payload).await?;
+
+    Ok((StatusCode::CREATED, Json(issued_api_key)))
+}
+
+pub async fn api_key_list(
+    _: AdminAuth,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Json<Vec<ApiKeyRecord>>, GatewayError> {
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+
+    Ok(Json(api_key_client.list_api_keys().await?))
+}
+
+pub async fn api_key_inspect(
+    _: AdminAuth,
+    Path(key_id): Path<String>,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Json<ApiKeyRecord>, GatewayError> {
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+
+    Ok(Json(api_key_client.get_api_key(&key_id).await?))
+}
+
+/// hands out a new key for the same record, the old key stops working
+pub async fn api_key_rotate(
+    _: AdminAuth,
+    Path(key_id): Path<String>,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Json<IssuedApiKey>, GatewayError> {
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+
+    Ok(Json(api_key_client.rotate_api_key(&key_id).await?))
+}
+
+/// turns the keys created by hand in redis into api keys, returns the migrated records
+pub async fn api_key_migrate_legacy(
+    _: AdminAuth,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Json<Vec<ApiKeyRecord>>, GatewayError> {
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+
+    Ok(Json(api_key_client.migrate_legacy_keys().await?))
+}
+
+pub async fn api_key_revoke(
+    _: AdminAuth,
+    Path(key_id): Path<String>,
+    Extension(app_state): Extension<AppState>,
+) -> Result<StatusCode, GatewayError> {
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+    api_key_client.revoke_api_key(&key_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,104 @@
+use crate::{
+    admin_auth::{bearer_token, constant_time_eq},
+    api_key_client::ApiKeyClient,
+    api_key_record::{legacy_key_id, split_api_key},
+    api_key_scope::ApiKeyScope,
+    app_state::AppState,
+    gateway_error::GatewayError,
+};
+use async_trait::async_trait;
+use axum::extract::{FromRequest, Path, RequestParts};
+use std::{collections::HashMap, fmt};
+
+/// An api key sent by a player, only its id shows up in logs
+///
+/// The key is read from `Authorization: Bearer <api key>`, the `/character/:sheet_key` path form is deprecated.
+pub struct ApiKey(pub String);
+
+#[async_trait]
+impl<B> FromRequest<B> for ApiKey
+where
+    B: Send,
+{
+    type Rejection = GatewayError;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        if let Some(api_key) = bearer_token(req.headers()) {
+            return Ok(ApiKey(api_key));
+        }
+
+        let path_key = Path::<HashMap<String, String>>::from_request(req)
+            .await
+            .ok()
+            .and_then(|Path(params)| params.get("sheet_key").cloned());
+
+        match path_key {
+            Some(api_key) => {
+                let api_key = ApiKey(api_key);
+                tracing::warn!(
+                    "{:?} was passed in the path, which is deprecated, send it as Authorization: Bearer header",
+                    api_key
+                );
+                Ok(api_key)
+            }
+            None => Err(GatewayError::Unauthorized("missing api key".to_string())),
+        }
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match split_api_key(&self.0) {
+            Some((key_id, _)) => write!(f, "ApiKey({}.<redacted>)", key_id),
+            None => write!(f, "ApiKey(legacy {})", legacy_key_id(&self.0)),
+        }
+    }
+}
+
+/// Extractor guarding the storyteller endpoints, accepts the admin token or a storyteller key
+pub struct StorytellerAuth {
+    /// `admin` or the id of the storyteller key
+    pub reviewer: String,
+    /// the sheet of the storyteller key, whose proposals are the only ones it sees, none for the admin token
+    pub sheet_id: Option<String>,
+}
+
+#[async_trait]
+impl<B> FromRequest<B> for StorytellerAuth
+where
+    B: Send,
+{
+    type Rejection = GatewayError;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let app_state = req
+            .extensions()
+            .get::<AppState>()
+            .cloned()
+            .ok_or_else(|| GatewayError::Internal("the app state is missing".to_string()))?;
+        let token = bearer_token(req.headers())
+            .ok_or_else(|| GatewayError::Unauthorized("missing bearer token".to_string()))?;
+
+        if let Some(admin_token) = &app_state.admin_token {
+            if constant_time_eq(token.as_bytes(), admin_token.as_bytes()) {
+                return Ok(StorytellerAuth {
+                    reviewer: "admin".to_string(),
+                    sheet_id: None,
+                });
+            }
+        }
+
+        let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+        let api_key_record = api_key_client.map_key(&token).await?;
+
+        match api_key_record.scope {
+            ApiKeyScope::Storyteller => Ok(StorytellerAuth {
+                reviewer: api_key_record.key_id,
+                sheet_id: Some(api_key_record.sheet_id),
+            }),
+            _ => Err(GatewayError::NotAllowed(
+                "a storyteller key is required".to_string(),
+            )),
+        }
+    }
+}
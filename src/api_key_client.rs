@@ -1,65 +1,337 @@
 extern crate redis;
 
-use std::env;
+use std::{collections::HashMap, env};
 
-use hyper::StatusCode;
-use redis::{Commands, Connection};
+use chrono::Utc;
+use redis::{aio::ConnectionManager, AsyncCommands};
 
-use crate::character_db::player_character::PlayerCharacter;
+use crate::{
+    api_key_record::{
+        legacy_key_id, looks_like_sheet_id, random_token, secret_fields, split_api_key,
+        verify_secret, ApiKeyInput, ApiKeyRecord, IssuedApiKey,
+    },
+    api_key_scope::ApiKeyScope,
+    character_db::player_character::PlayerCharacter,
+    gateway_error::GatewayError,
+};
+
+/// the prefixes of the redis keys the gateway stores itself, they can never be a hand made api key
+const RESERVED_PREFIXES: [&str; 5] = ["apikey:", "cache-", "proposal:", "lock:", "blood:"];
 
 pub struct ApiKeyClient {
-    connection: Connection,
+    connection: ConnectionManager,
 }
 
 impl ApiKeyClient {
-    pub fn new(connection_string: String) -> ApiKeyClient {
-        let client = redis::Client::open(connection_string).unwrap();
-        ApiKeyClient {
-            connection: client.get_connection().unwrap(),
+    pub fn new(connection: ConnectionManager) -> ApiKeyClient {
+        ApiKeyClient { connection }
+    }
+
+    /// looks up the record of an api key and notes that it was used
+    pub async fn map_key(&mut self, api_key: &str) -> Result<ApiKeyRecord, GatewayError> {
+        // migrated keys created by hand have no id, theirs is derived from the key
+        let mut candidates = vec![(legacy_key_id(api_key), api_key)];
+        if let Some((key_id, secret)) = split_api_key(api_key) {
+            candidates.insert(0, (key_id.to_string(), secret));
+        }
+
+        for (key_id, secret) in candidates {
+            let fields = self.get_fields(&key_id).await?;
+
+            if let Some(record) = ApiKeyRecord::from_fields(&key_id, &fields) {
+                if verify_secret(secret, &fields) {
+                    self.touch(&key_id).await;
+                    return Ok(record);
+                }
+            }
+        }
+
+        Err(GatewayError::UnknownApiKey)
+    }
+
+    /// replaces the keys created by hand (`SET <api key> <sheet id>`) by hashed records that accept the same key
+    ///
+    /// Only string keys outside the prefixes of the gateway whose value looks like a sheet id are migrated.
+    pub async fn migrate_legacy_keys(&mut self) -> Result<Vec<ApiKeyRecord>, GatewayError> {
+        let mut api_keys: Vec<String> = Vec::new();
+        {
+            let mut iter = self
+                .connection
+                .scan::<String>()
+                .await
+                .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+            while let Some(redis_key) = iter.next_item().await {
+                if !RESERVED_PREFIXES.iter().any(|x| redis_key.starts_with(x)) {
+                    api_keys.push(redis_key);
+                }
+            }
+        }
+
+        let mut records: Vec<ApiKeyRecord> = Vec::new();
+
+        for api_key in api_keys {
+            let key_type = redis::cmd("TYPE")
+                .arg(&api_key)
+                .query_async::<_, String>(&mut self.connection)
+                .await
+                .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+            if key_type != "string" {
+                continue;
+            }
+
+            let sheet_id = match self
+                .connection
+                .get::<&String, Option<String>>(&api_key)
+                .await
+                .map_err(|err| GatewayError::RedisFailure(err.to_string()))?
+            {
+                Some(sheet_id) if looks_like_sheet_id(&sheet_id) => sheet_id,
+                _ => {
+                    tracing::warn!(
+                        "skipping the redis key {}, its value is not a sheet id",
+                        legacy_key_id(&api_key)
+                    );
+                    continue;
+                }
+            };
+
+            let record = ApiKeyRecord {
+                key_id: legacy_key_id(&api_key),
+                sheet_id,
+                owner: String::new(),
+                player_name: String::new(),
+                scope: ApiKeyScope::default(),
+                created_at: Utc::now(),
+                last_used_at: None,
+            };
+
+            let mut fields = record.to_fields();
+            fields.extend(secret_fields(&api_key));
+            self.connection
+                .hset_multiple::<String, &str, String, ()>(
+                    ApiKeyRecord::redis_key(&record.key_id),
+                    &fields,
+                )
+                .await
+                .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+            self.connection
+                .del::<Vec<String>, ()>(vec![api_key.clone(), format!("cache-{}", api_key)])
+                .await
+                .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+            tracing::info!("migrated a legacy api key to {}", record.key_id);
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+
+    /// creates a new api key for a sheet
+    pub async fn create_api_key(
+        &mut self,
+        input: ApiKeyInput,
+    ) -> Result<IssuedApiKey, GatewayError> {
+        if input.sheet_id.trim().is_empty() {
+            return Err(GatewayError::BadRequest("sheet_id is missing".to_string()));
+        }
+
+        let record = ApiKeyRecord {
+            key_id: random_token(8),
+            sheet_id: input.sheet_id.trim().to_string(),
+            owner: input.owner,
+            player_name: input.player_name,
+            scope: input.scope,
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        let secret = random_token(32);
+
+        let mut fields = record.to_fields();
+        fields.extend(secret_fields(&secret));
+        self.connection
+            .hset_multiple::<String, &str, String, ()>(
+                ApiKeyRecord::redis_key(&record.key_id),
+                &fields,
+            )
+            .await
+            .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+        tracing::info!(
+            "created api key {} for sheet {}",
+            record.key_id,
+            record.sheet_id
+        );
+
+        Ok(IssuedApiKey {
+            api_key: format!("{}.{}", record.key_id, secret),
+            record,
+        })
+    }
+
+    /// all api keys, oldest first
+    pub async fn list_api_keys(&mut self) -> Result<Vec<ApiKeyRecord>, GatewayError> {
+        let mut records: Vec<ApiKeyRecord> = Vec::new();
+
+        for key_id in self.key_ids().await? {
+            let fields = self.get_fields(&key_id).await?;
+
+            if let Some(record) = ApiKeyRecord::from_fields(&key_id, &fields) {
+                records.push(record);
+            }
+        }
+        records.sort_by_key(|x| x.created_at);
+
+        Ok(records)
+    }
+
+    async fn key_ids(&mut self) -> Result<Vec<String>, GatewayError> {
+        let mut key_ids: Vec<String> = Vec::new();
+        let mut iter = self
+            .connection
+            .scan_match::<&str, String>("apikey:*")
+            .await
+            .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+        while let Some(redis_key) = iter.next_item().await {
+            key_ids.push(redis_key.trim_start_matches("apikey:").to_string());
         }
+
+        Ok(key_ids)
+    }
+
+    pub async fn get_api_key(&mut self, key_id: &str) -> Result<ApiKeyRecord, GatewayError> {
+        let fields = self.get_fields(key_id).await?;
+
+        ApiKeyRecord::from_fields(key_id, &fields)
+            .ok_or_else(|| GatewayError::ApiKeyNotFound(key_id.to_string()))
+    }
+
+    /// replaces the secret of an api key, the old key stops working right away
+    pub async fn rotate_api_key(&mut self, key_id: &str) -> Result<IssuedApiKey, GatewayError> {
+        let record = self.get_api_key(key_id).await?;
+        let secret = random_token(32);
+
+        self.connection
+            .hset_multiple::<String, &str, String, ()>(
+                ApiKeyRecord::redis_key(key_id),
+                &secret_fields(&secret),
+            )
+            .await
+            .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+        tracing::info!("rotated api key {}", key_id);
+
+        Ok(IssuedApiKey {
+            api_key: format!("{}.{}", record.key_id, secret),
+            record,
+        })
     }
 
-    pub fn map_key(&mut self, api_key: &String) -> Result<String, StatusCode> {
-        if let Ok(value) = self.connection.get::<&String, String>(api_key) {
-            Ok(value)
-        } else {
-            Err(StatusCode::NOT_FOUND)
+    /// deletes an api key, the cached character stays for the other keys of the sheet
+    pub async fn revoke_api_key(&mut self, key_id: &str) -> Result<(), GatewayError> {
+        let removed = self
+            .connection
+            .del::<String, usize>(ApiKeyRecord::redis_key(key_id))
+            .await
+            .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+        if removed == 0 {
+            return Err(GatewayError::ApiKeyNotFound(key_id.to_string()));
         }
+
+        tracing::info!("revoked api key {}", key_id);
+
+        Ok(())
+    }
+
+    async fn get_fields(&mut self, key_id: &str) -> Result<HashMap<String, String>, GatewayError> {
+        self.connection
+            .hgetall::<String, HashMap<String, String>>(ApiKeyRecord::redis_key(key_id))
+            .await
+            .map_err(|err| GatewayError::RedisFailure(err.to_string()))
     }
 
-    pub fn get_cached_data(&mut self, api_key: &String) -> Result<PlayerCharacter, StatusCode> {
-        let cache_key = format!("cache-{}", api_key);
+    /// stores when the key was last used, a failure only costs the timestamp
+    async fn touch(&mut self, key_id: &str) {
+        let result = self
+            .connection
+            .hset::<String, &str, String, ()>(
+                ApiKeyRecord::redis_key(key_id),
+                "last_used_at",
+                Utc::now().to_rfc3339(),
+            )
+            .await;
 
-        if let Ok(value) = self.connection.get::<String, String>(cache_key) {
-            let player_character: PlayerCharacter = serde_json::from_str(&value).unwrap();
-            Ok(player_character)
-        } else {
-            Err(StatusCode::NOT_FOUND)
+        if let Err(err) = result {
+            tracing::warn!(
+                "could not store the last use of api key {}: {:?}",
+                key_id,
+                err
+            );
         }
     }
 
-    pub fn write_player_character_to_cache(
+    /// the redis key of the cached character of a sheet, shared by all api keys of the sheet
+    pub fn cache_key(sheet_id: &str) -> String {
+        format!("cache-{}", sheet_id)
+    }
+
+    /// returns the cached character, unreadable cache entries are treated as missing
+    pub async fn get_cached_data(
         &mut self,
-        api_key: &String,
+        sheet_id: &str,
+    ) -> Result<Option<PlayerCharacter>, GatewayError> {
+        let cache_key = ApiKeyClient::cache_key(sheet_id);
+
+        let value = self
+            .connection
+            .get::<String, Option<String>>(cache_key)
+            .await
+            .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+        Ok(
+            value.and_then(|x| match serde_json::from_str::<PlayerCharacter>(&x) {
+                Ok(player_character) => Some(player_character),
+                Err(err) => {
+                    tracing::warn!("ignoring unreadable cache entry: {:?}", err);
+                    None
+                }
+            }),
+        )
+    }
+
+    pub async fn write_player_character_to_cache(
+        &mut self,
+        sheet_id: &str,
         player_character: &PlayerCharacter,
     ) {
-        let cache_key = format!("cache-{}", api_key);
-        let serialized_character = serde_json::to_string(player_character).unwrap();
+        let cache_key = ApiKeyClient::cache_key(sheet_id);
+        let serialized_character = match serde_json::to_string(player_character) {
+            Ok(value) => value,
+            Err(err) => {
+                tracing::error!("could not serialize character for the cache: {:?}", err);
+                return;
+            }
+        };
         let ttl: usize = env::var("CACHE_TTL")
-            .unwrap_or_else(|_| "900".to_string())
-            .parse()
-            .unwrap();
+            .ok()
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(900);
 
-        let result =
-            self.connection
-                .set_ex::<String, String, usize>(cache_key, serialized_character, ttl);
+        let result = self
+            .connection
+            .set_ex::<String, String, ()>(cache_key, serialized_character, ttl)
+            .await;
         tracing::debug!("cache write result: {:?}", result);
     }
 
-    pub fn remove_cached_data(&mut self, api_key: &String) -> Result<bool, StatusCode> {
-        let cache_key = format!("cache-{}", api_key);
+    /// removes the cached character of the sheet, every key of the sheet reads it again
+    pub async fn remove_cached_data(&mut self, sheet_id: &str) -> Result<bool, GatewayError> {
+        let cache_key = ApiKeyClient::cache_key(sheet_id);
 
-        let result = self.connection.del(cache_key);
+        let result = self.connection.del(cache_key).await;
 
         match result {
             Ok(value) => {
@@ -68,8 +340,57 @@ impl ApiKeyClient {
             }
             Err(error) => {
                 tracing::error!("Cache cleaning faield: {:?}", error);
-                Err(StatusCode::BAD_REQUEST)
+                Err(GatewayError::RedisFailure(error.to_string()))
+            }
+        }
+    }
+
+    /// removes the cached characters matching the filter, unreadable entries are removed as well
+    pub async fn invalidate_cached_characters<F>(
+        &mut self,
+        is_affected: F,
+    ) -> Result<usize, GatewayError>
+    where
+        F: Fn(&PlayerCharacter) -> bool,
+    {
+        let mut cache_keys: Vec<String> = Vec::new();
+        {
+            let mut iter = self
+                .connection
+                .scan_match::<&str, String>("cache-*")
+                .await
+                .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+            while let Some(cache_key) = iter.next_item().await {
+                cache_keys.push(cache_key);
+            }
+        }
+
+        let mut removed = 0;
+
+        for cache_key in cache_keys {
+            let value = self
+                .connection
+                .get::<&String, Option<String>>(&cache_key)
+                .await
+                .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+            let affected = match value {
+                Some(x) => serde_json::from_str::<PlayerCharacter>(&x)
+                    .map(|player_character| is_affected(&player_character))
+                    .unwrap_or(true),
+                None => false,
+            };
+
+            if affected {
+                self.connection
+                    .del::<&String, ()>(&cache_key)
+                    .await
+                    .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+                removed += 1;
             }
         }
+
+        Ok(removed)
     }
 }
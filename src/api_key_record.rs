@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use crate::{admin_auth::constant_time_eq, api_key_scope::ApiKeyScope};
+
+/// An api key as stored in the redis hash `apikey:<key_id>`
+///
+/// The key handed out to the player is `<key_id>.<secret>`, redis only holds a salted hash of the secret.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiKeyRecord {
+    pub key_id: String,
+    pub sheet_id: String,
+    pub owner: String,
+    pub player_name: String,
+    pub scope: ApiKeyScope,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKeyRecord {
+    /// the redis key of the hash holding the record
+    pub fn redis_key(key_id: &str) -> String {
+        format!("apikey:{}", key_id)
+    }
+
+    /// reads the record from the fields of its redis hash, `None` if the hash is empty or incomplete
+    pub fn from_fields(key_id: &str, fields: &HashMap<String, String>) -> Option<ApiKeyRecord> {
+        Some(ApiKeyRecord {
+            key_id: key_id.to_string(),
+            sheet_id: fields.get("sheet_id")?.clone(),
+            owner: fields.get("owner").cloned().unwrap_or_default(),
+            player_name: fields.get("player_name").cloned().unwrap_or_default(),
+            scope: fields
+                .get("scope")
+                .map(|x| ApiKeyScope::parse(x))
+                .unwrap_or_default(),
+            created_at: fields.get("created_at")?.parse().ok()?,
+            last_used_at: fields.get("last_used_at").and_then(|x| x.parse().ok()),
+        })
+    }
+
+    /// the fields of the redis hash, without the hashed secret
+    pub fn to_fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("sheet_id", self.sheet_id.clone()),
+            ("owner", self.owner.clone()),
+            ("player_name", self.player_name.clone()),
+            ("scope", self.scope.as_str().to_string()),
+            ("created_at", self.created_at.to_rfc3339()),
+        ];
+
+        if let Some(last_used_at) = self.last_used_at {
+            fields.push(("last_used_at", last_used_at.to_rfc3339()));
+        }
+
+        fields
+    }
+}
+
+/// the input for creating an api key
+#[derive(Deserialize, Debug)]
+pub struct ApiKeyInput {
+    pub sheet_id: String,
+    #[serde(default)]
+    pub owner: String,
+    #[serde(default)]
+    pub player_name: String,
+    #[serde(default)]
+    pub scope: ApiKeyScope,
+}
+
+/// a newly created or rotated api key, the only time the key is shown
+#[derive(Serialize, Debug)]
+pub struct IssuedApiKey {
+    pub api_key: String,
+    #[serde(flatten)]
+    pub record: ApiKeyRecord,
+}
+
+/// a random hex string made of the given number of bytes from the os random number generator
+pub fn random_token(bytes: usize) -> String {
+    let mut buffer = vec![0_u8; bytes];
+    OsRng.fill_bytes(&mut buffer);
+    hex::encode(buffer)
+}
+
+/// the salted sha256 hash of a secret, the secrets are random enough that a slow hash is not needed
+pub fn hash_secret(salt: &str, secret: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(secret.as_bytes());
+
+    hex::encode(hasher.finalize())
+}
+
+/// a new salt and the matching hash, as fields of the redis hash
+pub fn secret_fields(secret: &str) -> Vec<(&'static str, String)> {
+    let salt = random_token(16);
+    let secret_hash = hash_secret(&salt, secret);
+
+    vec![("salt", salt), ("secret_hash", secret_hash)]
+}
+
+/// whether the secret matches the salted hash stored in the fields of the redis hash
+pub fn verify_secret(secret: &str, fields: &HashMap<String, String>) -> bool {
+    match (fields.get("salt"), fields.get("secret_hash")) {
+        (Some(salt), Some(secret_hash)) => {
+            constant_time_eq(hash_secret(salt, secret).as_bytes(), secret_hash.as_bytes())
+        }
+        _ => false,
+    }
+}
+
+/// the id of a key created by hand before keys had ids, derived from the key itself
+pub fn legacy_key_id(api_key: &str) -> String {
+    hex::encode(Sha256::digest(api_key.as_bytes()))[..16].to_string()
+}
+
+/// splits a key handed out by the gateway into its id and secret
+pub fn split_api_key(api_key: &str) -> Option<(&str, &str)> {
+    let (key_id, secret) = api_key.split_once('.')?;
+
+    match key_id.is_empty() || secret.is_empty() {
+        true => None,
+        false => Some((key_id, secret)),
+    }
+}
+
+/// whether the value of a key created by hand is a sheet id, google ids only use letters, digits, `-` and `_`
+pub fn looks_like_sheet_id(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|x| x.is_ascii_alphanumeric() || x == '-' || x == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sheet_ids_are_recognized() {
+        assert!(looks_like_sheet_id(
+            "1BxiMVs0XRA5nFMdKvBdBZjgmUUqptlbs74OgvE2upms"
+        ));
+        assert!(looks_like_sheet_id("example-sheet"));
+    }
+
+    #[test]
+    fn other_values_are_no_sheet_ids() {
+        assert!(!looks_like_sheet_id(""));
+        assert!(!looks_like_sheet_id("{\"name\": \"Anna\"}"));
+        assert!(!looks_like_sheet_id("12 blood"));
+    }
+}
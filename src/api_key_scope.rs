@@ -0,0 +1,90 @@
+use crate::{
+    character_db::{character_diff::update_paths, player_character::PlayerCharacterUpdateInput},
+    gateway_error::GatewayError,
+};
+use serde::{Deserialize, Serialize};
+
+/// What an api key may do with its character
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// reading only
+    ReadOnly,
+    /// reading and changing the sections players edit themselves, the default for keys without a scope
+    #[default]
+    PlayerWrite,
+    /// reading and changing everything
+    Storyteller,
+}
+
+impl ApiKeyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::ReadOnly => "read_only",
+            ApiKeyScope::PlayerWrite => "player_write",
+            ApiKeyScope::Storyteller => "storyteller",
+        }
+    }
+
+    /// reads the scope stored in redis, unknown values only read so a typo never grants write access
+    pub fn parse(value: &str) -> ApiKeyScope {
+        match value {
+            "read_only" => ApiKeyScope::ReadOnly,
+            "player_write" => ApiKeyScope::PlayerWrite,
+            "storyteller" => ApiKeyScope::Storyteller,
+            _ => {
+                tracing::warn!("unknown api key scope {:?}, the key may only read", value);
+                ApiKeyScope::ReadOnly
+            }
+        }
+    }
+
+    /// rejects any update of a read only key, listing the paths it touches
+    ///
+    /// Which ranges only storytellers may write is part of the field configuration,
+    /// see `PlayerCharacterUpdateInput::into_value_ranges`.
+    pub fn check_update(&self, payload: &PlayerCharacterUpdateInput) -> Result<(), GatewayError> {
+        if *self != ApiKeyScope::ReadOnly {
+            return Ok(());
+        }
+
+        let forbidden_paths = update_paths(payload)?;
+
+        match forbidden_paths.is_empty() {
+            true => Ok(()),
+            false => Err(GatewayError::Forbidden(forbidden_paths)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_scopes_are_read_back() {
+        for scope in [
+            ApiKeyScope::ReadOnly,
+            ApiKeyScope::PlayerWrite,
+            ApiKeyScope::Storyteller,
+        ] {
+            assert_eq!(ApiKeyScope::parse(scope.as_str()), scope);
+        }
+    }
+
+    #[test]
+    fn unknown_scopes_can_not_write() {
+        let mut payload: PlayerCharacterUpdateInput = serde_json::from_str("{}").unwrap();
+        payload.character_name = Some("Johanna Schwarz".to_string());
+
+        for value in ["storyteler", "Storyteller", "admin", ""] {
+            let scope = ApiKeyScope::parse(value);
+
+            assert_eq!(scope, ApiKeyScope::ReadOnly);
+            assert!(matches!(
+                scope.check_update(&payload),
+                Err(GatewayError::Forbidden(_))
+            ));
+        }
+    }
+}
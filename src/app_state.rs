@@ -0,0 +1,49 @@
+use crate::{
+    character_db::character_source::{create_character_source, CharacterSource},
+    config::{
+        config_client::config_path,
+        sheet_layouts::{layout_directory, SheetLayouts},
+    },
+};
+use arc_swap::ArcSwap;
+use redis::aio::ConnectionManager;
+use std::{env, sync::Arc};
+
+/// Everything the handlers share, created once at startup
+#[derive(Clone)]
+pub struct AppState {
+    /// the backend holding the character sheets
+    pub character_source: Arc<dyn CharacterSource>,
+    /// a multiplexed redis connection which reconnects on its own
+    pub redis_connection: ConnectionManager,
+    /// the field configurations of all sheet versions, swapped as a whole on reload
+    pub layouts: Arc<ArcSwap<SheetLayouts>>,
+    /// where the layouts are reloaded from
+    pub config_path: String,
+    pub layout_directory: String,
+    /// the bearer token of the admin endpoints, they are disabled without one
+    pub admin_token: Option<String>,
+}
+
+impl AppState {
+    /// creates the shared state from the env variables and the already validated layouts
+    pub async fn from_env(layouts: SheetLayouts) -> AppState {
+        let db_connection_string =
+            env::var("DB_CONNECTION_STRING").unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
+        let redis_client =
+            redis::Client::open(db_connection_string).expect("invalid redis connection string");
+        let redis_connection = redis_client
+            .get_tokio_connection_manager()
+            .await
+            .expect("failed to connect to redis");
+
+        AppState {
+            character_source: create_character_source().await,
+            redis_connection,
+            layouts: Arc::new(ArcSwap::from_pointee(layouts)),
+            config_path: config_path(),
+            layout_directory: layout_directory(),
+            admin_token: env::var("ADMIN_TOKEN").ok().filter(|x| !x.is_empty()),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use serde::Deserialize;
+
+/// A change of the current blood, applied in redis in a single step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BloodChange {
+    /// reads the current blood without changing it
+    Inspect,
+    /// spends blood in the current round
+    Spend(u8),
+    /// adds blood, the pool does not overflow
+    Feed(u8),
+    /// fills the pool and starts with the first round
+    Reset,
+    /// starts the next round, the blood per turn can be spent again
+    NewRound,
+}
+
+impl BloodChange {
+    /// the name of the operation as the script of the blood client knows it
+    pub fn operation(&self) -> &'static str {
+        match self {
+            BloodChange::Inspect => "inspect",
+            BloodChange::Spend(_) => "spend",
+            BloodChange::Feed(_) => "feed",
+            BloodChange::Reset => "reset",
+            BloodChange::NewRound => "new_round",
+        }
+    }
+
+    pub fn amount(&self) -> u8 {
+        match self {
+            BloodChange::Spend(amount) | BloodChange::Feed(amount) => *amount,
+            BloodChange::Inspect | BloodChange::Reset | BloodChange::NewRound => 0,
+        }
+    }
+}
+
+/// the body of a request spending or feeding blood
+#[derive(Deserialize, Debug)]
+pub struct BloodChangeInput {
+    pub amount: u8,
+}
@@ -0,0 +1,90 @@
+use crate::{
+    blood_change::BloodChange, blood_status::BloodStatus,
+    character_db::player_character::PlayerCharacter, gateway_error::GatewayError,
+};
+use redis::aio::ConnectionManager;
+
+/// reads and changes the blood of a sheet in one step, so keys sharing the sheet spend from the same pool
+///
+/// The hash holds `current`, `round` and `spent`, a sheet without it is full and in its first round.
+/// The status of the reply is 0 if the change was applied, 1 if it exceeds the blood per turn and 2
+/// if the character does not have that much blood.
+const BLOOD_SCRIPT: &str = r#"
+local key = KEYS[1]
+local operation = ARGV[1]
+local amount = tonumber(ARGV[2])
+local blood_pool = tonumber(ARGV[3])
+local blood_per_turn = tonumber(ARGV[4])
+
+local current = math.min(tonumber(redis.call("HGET", key, "current")) or blood_pool, blood_pool)
+local round = tonumber(redis.call("HGET", key, "round")) or 1
+local spent = tonumber(redis.call("HGET", key, "spent")) or 0
+
+if operation == "spend" then
+    if spent + amount > blood_per_turn then
+        return {1, current, round, spent}
+    end
+    if amount > current then
+        return {2, current, round, spent}
+    end
+    current = current - amount
+    spent = spent + amount
+elseif operation == "feed" then
+    current = math.min(current + amount, blood_pool)
+elseif operation == "reset" then
+    current = blood_pool
+    round = 1
+    spent = 0
+elseif operation == "new_round" then
+    round = round + 1
+    spent = 0
+end
+
+if operation ~= "inspect" then
+    redis.call("HSET", key, "current", current)
+    redis.call("HSET", key, "round", round)
+    redis.call("HSET", key, "spent", spent)
+end
+
+return {0, current, round, spent}
+"#;
+
+/// Keeps the current blood of each sheet in redis, the sheet itself only knows the size of the pool
+pub struct BloodClient {
+    connection: ConnectionManager,
+}
+
+impl BloodClient {
+    pub fn new(connection: ConnectionManager) -> BloodClient {
+        BloodClient { connection }
+    }
+
+    pub fn redis_key(sheet_id: &str) -> String {
+        format!("blood:{}", sheet_id)
+    }
+
+    /// applies the change within the limits of the character and returns the blood afterwards
+    pub async fn change(
+        &mut self,
+        sheet_id: &str,
+        player_character: &PlayerCharacter,
+        change: BloodChange,
+    ) -> Result<BloodStatus, GatewayError> {
+        let reply = redis::Script::new(BLOOD_SCRIPT)
+            .key(BloodClient::redis_key(sheet_id))
+            .arg(change.operation())
+            .arg(change.amount())
+            .arg(player_character.blood_pool)
+            .arg(player_character.blood_per_turn)
+            .invoke_async::<_, Vec<i64>>(&mut self.connection)
+            .await
+            .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+        BloodStatus::from_reply(
+            player_character.blood_pool,
+            player_character.blood_per_turn,
+            change.amount(),
+            &reply,
+        )
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    api_key_auth::ApiKey,
+    api_key_client::ApiKeyClient,
+    api_key_record::ApiKeyRecord,
+    api_key_scope::ApiKeyScope,
+    app_state::AppState,
+    blood_change::{BloodChange, BloodChangeInput},
+    blood_client::BloodClient,
+    blood_status::BloodStatus,
+    character_db::{
+        player_character::PlayerCharacter, player_character_client::PlayerCharacterClient,
+    },
+    gateway_error::GatewayError,
+};
+use axum::{Extension, Json};
+
+/// the path reported when a key may not change the current blood
+const BLOOD_PATH: &str = "current_blood";
+
+/// the current blood of the character along with its pool, the blood it may spend per turn and the round
+pub async fn blood_inspect(
+    api_key: ApiKey,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Json<BloodStatus>, GatewayError> {
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+    let api_key_record = api_key_client.map_key(&api_key.0).await?;
+
+    let player_character = load_character(&mut api_key_client, &api_key_record, &app_state).await?;
+    let mut blood_client = BloodClient::new(app_state.redis_connection.clone());
+    let blood = blood_client
+        .change(
+            &api_key_record.sheet_id,
+            &player_character,
+            BloodChange::Inspect,
+        )
+        .await?;
+
+    Ok(Json(blood))
+}
+
+/// spends blood in the current round, up to the blood per turn over all spends of the round
+pub async fn blood_spend(
+    api_key: ApiKey,
+    Extension(app_state): Extension<AppState>,
+    Json(input): Json<BloodChangeInput>,
+) -> Result<Json<BloodStatus>, GatewayError> {
+    change_blood(api_key, app_state, BloodChange::Spend(input.amount)).await
+}
+
+pub async fn blood_feed(
+    api_key: ApiKey,
+    Extension(app_state): Extension<AppState>,
+    Json(input): Json<BloodChangeInput>,
+) -> Result<Json<BloodStatus>, GatewayError> {
+    change_blood(api_key, app_state, BloodChange::Feed(input.amount)).await
+}
+
+/// fills the pool, e.g. at the start of a game
+pub async fn blood_reset(
+    api_key: ApiKey,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Json<BloodStatus>, GatewayError> {
+    change_blood(api_key, app_state, BloodChange::Reset).await
+}
+
+/// starts the next round, the blood per turn can be spent again
+pub async fn blood_new_round(
+    api_key: ApiKey,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Json<BloodStatus>, GatewayError> {
+    change_blood(api_key, app_state, BloodChange::NewRound).await
+}
+
+/// like the health, blood changes too often during a game to wait for a storyteller
+async fn change_blood(
+    api_key: ApiKey,
+    app_state: AppState,
+    change: BloodChange,
+) -> Result<Json<BloodStatus>, GatewayError> {
+    tracing::debug!("{:?}", api_key);
+
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+    let api_key_record = api_key_client.map_key(&api_key.0).await?;
+
+    if api_key_record.scope == ApiKeyScope::ReadOnly {
+        return Err(GatewayError::Forbidden(vec![BLOOD_PATH.to_string()]));
+    }
+
+    let player_character = load_character(&mut api_key_client, &api_key_record, &app_state).await?;
+    let mut blood_client = BloodClient::new(app_state.redis_connection.clone());
+    let blood = blood_client
+        .change(&api_key_record.sheet_id, &player_character, change)
+        .await?;
+
+    Ok(Json(blood))
+}
+
+/// the cached character, the pool only changes when the sheet does
+async fn load_character(
+    api_key_client: &mut ApiKeyClient,
+    api_key_record: &ApiKeyRecord,
+    app_state: &AppState,
+) -> Result<PlayerCharacter, GatewayError> {
+    if let Some(player_character) = api_key_client
+        .get_cached_data(&api_key_record.sheet_id)
+        .await?
+    {
+        return Ok(player_character);
+    }
+
+    let player_character_client = PlayerCharacterClient::new(
+        app_state.character_source.clone(),
+        app_state.layouts.load_full(),
+    );
+    let player_character = player_character_client
+        .parse_data(api_key_record.sheet_id.clone())
+        .await?;
+
+    api_key_client
+        .write_player_character_to_cache(&api_key_record.sheet_id, &player_character)
+        .await;
+
+    Ok(player_character)
+}
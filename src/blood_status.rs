@@ -0,0 +1,126 @@
+use crate::gateway_error::GatewayError;
+use serde::{Deserialize, Serialize};
+
+/// the script applied the change
+const APPLIED: i64 = 0;
+/// the spent blood would exceed the blood per turn of the round
+const PER_TURN_EXCEEDED: i64 = 1;
+/// the character does not have that much blood
+const INSUFFICIENT: i64 = 2;
+
+/// The blood a character has right now and what it spent in the current round, along with the limits the sheet sets
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BloodStatus {
+    pub current: u8,
+    pub blood_pool: u8,
+    pub blood_per_turn: u8,
+    /// counts up from 1 with every new round
+    pub round: u32,
+    pub spent_this_round: u8,
+}
+
+impl BloodStatus {
+    /// reads the reply of the blood script, `[status, current, round, spent]`
+    pub fn from_reply(
+        blood_pool: u8,
+        blood_per_turn: u8,
+        requested: u8,
+        reply: &[i64],
+    ) -> Result<BloodStatus, GatewayError> {
+        let [status, current, round, spent] = reply else {
+            return Err(GatewayError::Internal(format!(
+                "unexpected reply of the blood script: {:?}",
+                reply
+            )));
+        };
+        let current = clamp(*current);
+        let spent_this_round = clamp(*spent);
+
+        match *status {
+            APPLIED => Ok(BloodStatus {
+                current,
+                blood_pool,
+                blood_per_turn,
+                round: (*round).clamp(1, u32::MAX as i64) as u32,
+                spent_this_round,
+            }),
+            PER_TURN_EXCEEDED => Err(GatewayError::BloodPerTurnExceeded {
+                requested,
+                spent_this_round,
+                blood_per_turn,
+            }),
+            INSUFFICIENT => Err(GatewayError::InsufficientBlood {
+                requested,
+                available: current,
+            }),
+            status => Err(GatewayError::Internal(format!(
+                "unknown status {} of the blood script",
+                status
+            ))),
+        }
+    }
+}
+
+fn clamp(value: i64) -> u8 {
+    value.clamp(0, u8::MAX as i64) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_applied_change_is_the_new_status() {
+        let blood = BloodStatus::from_reply(13, 2, 1, &[APPLIED, 11, 3, 2]).unwrap();
+
+        assert_eq!(
+            blood,
+            BloodStatus {
+                current: 11,
+                blood_pool: 13,
+                blood_per_turn: 2,
+                round: 3,
+                spent_this_round: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn spending_past_the_blood_per_turn_is_reported_with_the_spent_blood() {
+        let result = BloodStatus::from_reply(13, 2, 1, &[PER_TURN_EXCEEDED, 11, 1, 2]);
+
+        assert!(matches!(
+            result,
+            Err(GatewayError::BloodPerTurnExceeded {
+                requested: 1,
+                spent_this_round: 2,
+                blood_per_turn: 2,
+            })
+        ));
+    }
+
+    #[test]
+    fn spending_more_than_is_left_is_reported_with_the_current_blood() {
+        let result = BloodStatus::from_reply(13, 2, 2, &[INSUFFICIENT, 1, 1, 0]);
+
+        assert!(matches!(
+            result,
+            Err(GatewayError::InsufficientBlood {
+                requested: 2,
+                available: 1,
+            })
+        ));
+    }
+
+    #[test]
+    fn unexpected_replies_are_internal_errors() {
+        assert!(matches!(
+            BloodStatus::from_reply(13, 2, 0, &[APPLIED, 13]),
+            Err(GatewayError::Internal(_))
+        ));
+        assert!(matches!(
+            BloodStatus::from_reply(13, 2, 0, &[7, 13, 1, 0]),
+            Err(GatewayError::Internal(_))
+        ));
+    }
+}
@@ -8,7 +8,7 @@ pub struct Attribute {
 }
 
 /// the struct for updating a single attribute
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AttributeUpdateInput {
     pub value: Option<u8>,
     pub foci: Option<Vec<String>>,
@@ -10,7 +10,7 @@ pub struct Attributes {
 }
 
 /// the struct for updating attributes
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AttributesUpdateInput {
     pub physical: Option<AttributeUpdateInput>,
     pub social: Option<AttributeUpdateInput>,
@@ -9,7 +9,7 @@ pub struct Background {
 }
 
 /// Input for updating a background
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BackgroundUpdateInput {
     pub name: String,
     pub value: u8,
@@ -1,7 +1,7 @@
 use super::{
     battle_base_information::BattleBaseInformation,
     battle_defense_information::BattleDefenseInformation,
-    battle_offense_information::BattleOffenseInformation,
+    battle_offense_information::{BattleOffenseInformation, BattleOffenseUpdateInput},
 };
 use serde::{Deserialize, Serialize};
 
@@ -12,3 +12,9 @@ pub struct BattleInformation {
     pub defense: BattleDefenseInformation,
     pub offense: Vec<BattleOffenseInformation>,
 }
+
+/// Input for updating the battle section, only the attack pools are not computed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BattleInformationUpdateInput {
+    pub offense: Option<Vec<BattleOffenseUpdateInput>>,
+}
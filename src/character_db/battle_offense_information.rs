@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::name_value::NameValue;
+use super::name_value::{NameUpdateInput, NameValue};
 
 /// BattleOffenseInformation Struct
 #[derive(Serialize, Deserialize, Debug)]
@@ -11,3 +11,13 @@ pub struct BattleOffenseInformation {
     pub pool: u8,
     pub description: String,
 }
+
+/// Input for updating an attack pool, the values of the selections and the pool are computed by the sheet
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BattleOffenseUpdateInput {
+    pub skill: NameUpdateInput,
+    pub attribute: NameUpdateInput,
+    pub wildcard: Option<NameUpdateInput>,
+    #[serde(default)]
+    pub description: String,
+}
@@ -0,0 +1,74 @@
+use super::player_character::{PlayerCharacter, PlayerCharacterUpdateInput};
+use crate::gateway_error::GatewayError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A value of the character an update would change
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub path: String,
+    pub current: Value,
+    pub proposed: Value,
+}
+
+/// the values of the character the update changes, values it sets to what they already are are left out
+pub fn diff_update(
+    current: &PlayerCharacter,
+    update: &PlayerCharacterUpdateInput,
+) -> Result<Vec<FieldChange>, GatewayError> {
+    let current =
+        serde_json::to_value(current).map_err(|err| GatewayError::Internal(err.to_string()))?;
+    let paths = update_paths(update)?;
+    let update =
+        serde_json::to_value(update).map_err(|err| GatewayError::Internal(err.to_string()))?;
+
+    Ok(paths
+        .into_iter()
+        .filter_map(|path| {
+            let pointer = format!("/{}", path.replace('.', "/"));
+            let proposed = update.pointer(&pointer).cloned().unwrap_or(Value::Null);
+            let current = current.pointer(&pointer).cloned().unwrap_or(Value::Null);
+
+            match current == proposed {
+                true => None,
+                false => Some(FieldChange {
+                    path,
+                    current,
+                    proposed,
+                }),
+            }
+        })
+        .collect())
+}
+
+/// the dotted paths of all values set in the update, lists count as a single value
+pub fn update_paths(update: &PlayerCharacterUpdateInput) -> Result<Vec<String>, GatewayError> {
+    let value =
+        serde_json::to_value(update).map_err(|err| GatewayError::Internal(err.to_string()))?;
+    let mut paths: Vec<String> = Vec::new();
+    collect_paths(&value, String::new(), &mut paths);
+
+    Ok(paths)
+}
+
+fn collect_paths(value: &Value, path: String, paths: &mut Vec<String>) {
+    match value {
+        Value::Null => {}
+        // either values like the generation are a single value as well
+        Value::Object(map)
+            if map.len() == 1 && (map.contains_key("Left") || map.contains_key("Right")) =>
+        {
+            paths.push(path)
+        }
+        Value::Object(map) => {
+            for (key, value) in map {
+                let child_path = match path.is_empty() {
+                    true => key.clone(),
+                    false => format!("{}.{}", path, key),
+                };
+                collect_paths(value, child_path, paths);
+            }
+        }
+        _ => paths.push(path),
+    }
+}
@@ -0,0 +1,113 @@
+use super::player_character::{PlayerCharacter, PlayerCharacterUpdateInput};
+use crate::gateway_error::GatewayError;
+use json_patch::Patch;
+use serde_json::{Map, Value};
+
+pub const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+pub const JSON_PATCH_CONTENT_TYPE: &str = "application/json-patch+json";
+
+/// A patch against the json of the character, as sent to `PATCH /character`
+#[derive(Debug, Clone)]
+pub enum CharacterPatch {
+    /// RFC 7396, an object whose values replace those of the character, `null` removes them
+    Merge(Value),
+    /// RFC 6902, a list of operations like `add /merits/-` or `remove /rituals/2`
+    Operations(Patch),
+}
+
+impl CharacterPatch {
+    /// reads the body according to its content type
+    pub fn parse(content_type: Option<&str>, body: &[u8]) -> Result<CharacterPatch, GatewayError> {
+        // parameters like `; charset=utf-8` do not change the format
+        let media_type = content_type
+            .and_then(|x| x.split(';').next())
+            .map(|x| x.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        match media_type.as_str() {
+            MERGE_PATCH_CONTENT_TYPE => serde_json::from_slice(body)
+                .map(CharacterPatch::Merge)
+                .map_err(|err| GatewayError::BadRequest(err.to_string())),
+            JSON_PATCH_CONTENT_TYPE => serde_json::from_slice(body)
+                .map(CharacterPatch::Operations)
+                .map_err(|err| GatewayError::BadRequest(err.to_string())),
+            _ => Err(GatewayError::UnsupportedMediaType(media_type)),
+        }
+    }
+
+    /// applies the patch to the character and returns the update writing the values it changed
+    ///
+    /// Values the patch leaves as they are are not part of the update, so computed values and
+    /// sections outside the scope of the api key only matter if the patch really changes them.
+    pub fn resolve(
+        &self,
+        current: &PlayerCharacter,
+    ) -> Result<PlayerCharacterUpdateInput, GatewayError> {
+        let current =
+            serde_json::to_value(current).map_err(|err| GatewayError::Internal(err.to_string()))?;
+        let mut patched = current.clone();
+
+        match self {
+            CharacterPatch::Merge(merge_patch) => json_patch::merge(&mut patched, merge_patch),
+            CharacterPatch::Operations(operations) => {
+                json_patch::patch(&mut patched, operations)
+                    .map_err(|err| GatewayError::InvalidPatch(err.to_string()))?
+            }
+        }
+
+        // both sides go through the update input, so only the values it can write are compared
+        let current = update_value(current)?;
+        let patched = update_value(patched)?;
+
+        let mut changes = match changed_values(&current, &patched) {
+            Some(Value::Object(changes)) => changes,
+            _ => Map::new(),
+        };
+
+        // merits and flaws share a range, writing one of them alone would clear the other
+        if changes.contains_key("merits") || changes.contains_key("flaws") {
+            for section in ["merits", "flaws"] {
+                if let Some(value) = patched.get(section) {
+                    changes.insert(section.to_string(), value.clone());
+                }
+            }
+        }
+
+        serde_json::from_value(Value::Object(changes))
+            .map_err(|err| GatewayError::Internal(err.to_string()))
+    }
+}
+
+/// the value as update input, a patched value of the wrong type is rejected here
+fn update_value(value: Value) -> Result<Value, GatewayError> {
+    let update: PlayerCharacterUpdateInput =
+        serde_json::from_value(value).map_err(|err| GatewayError::InvalidPatch(err.to_string()))?;
+
+    serde_json::to_value(update).map_err(|err| GatewayError::Internal(err.to_string()))
+}
+
+/// the parts of the patched value which differ, lists and either values are replaced as a whole
+fn changed_values(current: &Value, patched: &Value) -> Option<Value> {
+    match (current, patched) {
+        (Value::Object(current), Value::Object(patched)) if !is_either(patched) => {
+            let changes: Map<String, Value> = patched
+                .iter()
+                .filter_map(|(key, value)| {
+                    let current = current.get(key).unwrap_or(&Value::Null);
+                    changed_values(current, value).map(|x| (key.clone(), x))
+                })
+                .collect();
+
+            match changes.is_empty() {
+                true => None,
+                false => Some(Value::Object(changes)),
+            }
+        }
+        (current, patched) if current == patched => None,
+        (_, patched) => Some(patched.clone()),
+    }
+}
+
+fn is_either(map: &Map<String, Value>) -> bool {
+    map.len() == 1 && (map.contains_key("Left") || map.contains_key("Right"))
+}
@@ -0,0 +1,68 @@
+use super::{
+    google_sheets_source::GoogleSheetsSource, in_memory_source::InMemorySource,
+    workbook_source::WorkbookSource,
+};
+use crate::gateway_error::GatewayError;
+use async_trait::async_trait;
+use google_sheets4::api::ValueRange;
+use std::{env, sync::Arc};
+
+/// A backend holding the character sheets, addressed by sheet id and A1 ranges
+#[async_trait]
+pub trait CharacterSource: Send + Sync {
+    /// loads the passed in ranges, the result has the same order as the requested ranges
+    async fn load_ranges(
+        &self,
+        sheet_id: &str,
+        ranges: &[String],
+    ) -> Result<Vec<ValueRange>, GatewayError>;
+
+    /// writes the passed in ranges and returns the number of updated cells
+    async fn update_ranges(
+        &self,
+        sheet_id: &str,
+        data: Vec<ValueRange>,
+    ) -> Result<i32, GatewayError>;
+}
+
+/// creates the character source selected by the `CHARACTER_SOURCE` env variable
+pub async fn create_character_source() -> Arc<dyn CharacterSource> {
+    let source_type = env::var("CHARACTER_SOURCE").unwrap_or_else(|_| "google".to_string());
+
+    match source_type.as_str() {
+        "memory" => {
+            let fixture_path = env::var("CHARACTER_FIXTURE").ok();
+            tracing::debug!(
+                "using in-memory character source, fixture: {:?}",
+                fixture_path
+            );
+
+            match fixture_path {
+                Some(path) => Arc::new(
+                    InMemorySource::from_fixture_file(&path).expect("failed to load fixture file"),
+                ),
+                None => Arc::new(InMemorySource::new()),
+            }
+        }
+        "workbook" => {
+            let directory =
+                env::var("CHARACTER_DIRECTORY").unwrap_or_else(|_| "characters".to_string());
+            tracing::debug!(
+                "using workbook character source, directory: {:?}",
+                directory
+            );
+
+            Arc::new(WorkbookSource::new(directory))
+        }
+        _ => {
+            tracing::debug!("using google sheets character source");
+            let service_account_info = env::var("SERVICE_ACCOUNT_INFORMATION").unwrap_or_default();
+
+            Arc::new(
+                GoogleSheetsSource::new(service_account_info)
+                    .await
+                    .expect("failed to create google sheets source"),
+            )
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::{
+    character_patch::CharacterPatch,
+    player_character::{PlayerCharacter, PlayerCharacterUpdateInput},
+};
+use crate::{api_key_scope::ApiKeyScope, gateway_error::GatewayError};
+
+/// What a `PUT` or `PATCH` of the character changes
+#[derive(Debug, Clone)]
+pub enum CharacterUpdate {
+    /// the sections of the update input replace those of the sheet
+    Replace(Box<PlayerCharacterUpdateInput>),
+    /// the patch is applied to the character as the sheet holds it right now
+    Patch(CharacterPatch),
+}
+
+impl CharacterUpdate {
+    /// whether the current character has to be read to know what the update writes
+    pub fn needs_current(&self) -> bool {
+        matches!(self, CharacterUpdate::Patch(_))
+    }
+
+    /// the update input to write, checked against the scope of the api key
+    pub fn resolve(
+        self,
+        current: Option<&PlayerCharacter>,
+        scope: ApiKeyScope,
+    ) -> Result<PlayerCharacterUpdateInput, GatewayError> {
+        let payload = match (self, current) {
+            (CharacterUpdate::Replace(payload), _) => *payload,
+            (CharacterUpdate::Patch(patch), Some(current)) => patch.resolve(current)?,
+            (CharacterUpdate::Patch(_), None) => {
+                return Err(GatewayError::Internal(
+                    "a patch needs the current character".to_string(),
+                ))
+            }
+        };
+
+        scope.check_update(&payload)?;
+
+        Ok(payload)
+    }
+}
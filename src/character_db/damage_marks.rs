@@ -0,0 +1,91 @@
+use std::{env, sync::OnceLock};
+
+/// the marks read once at startup, the defaults until then
+static DAMAGE_MARKS: OnceLock<DamageMarks> = OnceLock::new();
+
+/// The marks written into a health box for each kind of damage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DamageMarks {
+    pub normal: String,
+    pub aggravated: String,
+}
+
+impl Default for DamageMarks {
+    fn default() -> Self {
+        DamageMarks {
+            normal: "x".to_string(),
+            aggravated: "*".to_string(),
+        }
+    }
+}
+
+impl DamageMarks {
+    /// checks the marks, boxes are read ignoring case so the marks may not only differ in case
+    pub fn new(normal: &str, aggravated: &str) -> Result<DamageMarks, String> {
+        let normal = normal.trim();
+        let aggravated = aggravated.trim();
+
+        if normal.is_empty() || aggravated.is_empty() {
+            return Err("the damage marks can not be empty".to_string());
+        }
+
+        if normal.eq_ignore_ascii_case(aggravated) {
+            return Err(format!(
+                "normal and aggravated damage can not both be marked with {}",
+                normal
+            ));
+        }
+
+        Ok(DamageMarks {
+            normal: normal.to_string(),
+            aggravated: aggravated.to_string(),
+        })
+    }
+
+    /// reads `NORMAL_DAMAGE_MARK` (default `x`) and `AGGRAVATED_DAMAGE_MARK` (default `*`)
+    pub fn from_env() -> Result<DamageMarks, String> {
+        let default = DamageMarks::default();
+
+        DamageMarks::new(
+            &env::var("NORMAL_DAMAGE_MARK").unwrap_or(default.normal),
+            &env::var("AGGRAVATED_DAMAGE_MARK").unwrap_or(default.aggravated),
+        )
+    }
+
+    /// makes the marks the ones used by every parse and write, only the first call counts
+    pub fn install(self) {
+        if DAMAGE_MARKS.set(self).is_err() {
+            tracing::warn!("the damage marks were already set, the new ones are ignored");
+        }
+    }
+
+    /// the marks in use
+    pub fn current() -> &'static DamageMarks {
+        DAMAGE_MARKS.get_or_init(DamageMarks::default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distinct_marks_are_accepted() {
+        let marks = DamageMarks::new(" / ", "X").unwrap();
+
+        assert_eq!(marks.normal, "/");
+        assert_eq!(marks.aggravated, "X");
+    }
+
+    #[test]
+    fn empty_marks_are_rejected() {
+        assert!(DamageMarks::new("", "*").is_err());
+        assert!(DamageMarks::new("x", "  ").is_err());
+    }
+
+    #[test]
+    fn equal_marks_are_rejected() {
+        assert!(DamageMarks::new("x", "x").is_err());
+        assert!(DamageMarks::new("x", "X").is_err());
+    }
+}
@@ -8,7 +8,7 @@ pub struct Discipline {
 }
 
 /// input for updating a discipline
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DisciplineUpdateInput {
     pub name: String,
     pub value: u8,
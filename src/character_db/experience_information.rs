@@ -10,7 +10,7 @@ pub struct ExperienceInformation {
 }
 
 /// Input for updating the experience section
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ExperienceInformationUpdateInput {
     pub start_value: u8,
 }
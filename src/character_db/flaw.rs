@@ -9,7 +9,7 @@ pub struct Flaw {
 }
 
 /// Input for updating a flaw
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FlawUpdateInput {
     pub name: String,
     pub flaw_type: String,
@@ -0,0 +1,140 @@
+extern crate google_sheets4 as sheets4;
+extern crate yup_oauth2 as oauth2;
+
+use super::character_source::CharacterSource;
+use crate::gateway_error::GatewayError;
+use async_trait::async_trait;
+use hyper::{client::HttpConnector, StatusCode};
+use hyper_rustls::HttpsConnector;
+use sheets4::{
+    api::{BatchUpdateValuesRequest, ValueRange},
+    Sheets,
+};
+
+/// Character source backed by the google spreadsheets api
+///
+/// Created once at startup, the authenticator of the hub caches the access token until it expires.
+pub struct GoogleSheetsSource {
+    hub: Sheets<HttpsConnector<HttpConnector>>,
+}
+
+impl GoogleSheetsSource {
+    pub async fn new(
+        service_account_information: String,
+    ) -> Result<GoogleSheetsSource, GatewayError> {
+        let secret = if let Ok(credentials) =
+            oauth2::parse_service_account_key(&service_account_information)
+        {
+            tracing::debug!("parsed credentials successfully from env variable");
+            credentials
+        } else {
+            tracing::debug!(
+                "parsed credentials unsuccessfully from env variable, trying credentials.json now"
+            );
+            if let Ok(credentials) = oauth2::read_service_account_key("credentials.json").await {
+                tracing::debug!("parsed credentials successfully from credentials.json");
+                credentials
+            } else {
+                tracing::debug!("both parse attempts failed, giving up");
+                return Err(GatewayError::GoogleAuthFailure(
+                    "no valid service account information found".to_string(),
+                ));
+            }
+        };
+
+        let authenticator = oauth2::ServiceAccountAuthenticator::builder(secret)
+            .build()
+            .await
+            .map_err(|err| GatewayError::GoogleAuthFailure(err.to_string()))?;
+
+        let hub = Sheets::new(
+            hyper::Client::builder().build(
+                hyper_rustls::HttpsConnectorBuilder::new()
+                    .with_native_roots()
+                    .https_or_http()
+                    .enable_http1()
+                    .enable_http2()
+                    .build(),
+            ),
+            authenticator,
+        );
+
+        Ok(GoogleSheetsSource { hub })
+    }
+}
+
+/// translates the errors of the google api into gateway errors
+fn map_sheets_error(sheet_id: &str, err: sheets4::Error) -> GatewayError {
+    tracing::debug!("google api error: {:?}", err);
+
+    let status = match &err {
+        sheets4::Error::BadRequest(value) => value["error"]["code"]
+            .as_u64()
+            .and_then(|x| StatusCode::from_u16(x as u16).ok()),
+        sheets4::Error::Failure(response) => Some(response.status()),
+        _ => None,
+    };
+
+    match (status, err) {
+        (_, sheets4::Error::MissingToken(token_error)) => {
+            GatewayError::GoogleAuthFailure(token_error.to_string())
+        }
+        (Some(StatusCode::NOT_FOUND | StatusCode::FORBIDDEN), _) => {
+            GatewayError::SheetNotFound(sheet_id.to_string())
+        }
+        (Some(StatusCode::TOO_MANY_REQUESTS), _) => GatewayError::QuotaExceeded,
+        (Some(StatusCode::UNAUTHORIZED), err) => GatewayError::GoogleAuthFailure(err.to_string()),
+        (Some(StatusCode::BAD_REQUEST), err) => GatewayError::InvalidRange(err.to_string()),
+        (_, err) => GatewayError::SourceFailure(err.to_string()),
+    }
+}
+
+#[async_trait]
+impl CharacterSource for GoogleSheetsSource {
+    async fn load_ranges(
+        &self,
+        sheet_id: &str,
+        ranges: &[String],
+    ) -> Result<Vec<ValueRange>, GatewayError> {
+        let mut result = self.hub.spreadsheets().values_batch_get(sheet_id);
+
+        for range in ranges {
+            result = result.add_ranges(range);
+        }
+
+        let response = result.doit().await;
+
+        match response {
+            Ok(my_result) => Ok(my_result.1.value_ranges.unwrap_or_default()),
+            Err(err) => Err(map_sheets_error(sheet_id, err)),
+        }
+    }
+
+    async fn update_ranges(
+        &self,
+        sheet_id: &str,
+        data: Vec<ValueRange>,
+    ) -> Result<i32, GatewayError> {
+        let update_request = BatchUpdateValuesRequest {
+            data: Some(data),
+            include_values_in_response: None,
+            response_date_time_render_option: None,
+            response_value_render_option: None,
+            value_input_option: Some("USER_ENTERED".to_string()),
+        };
+
+        tracing::trace!("update_request: {:?}", update_request);
+
+        let response = self
+            .hub
+            .spreadsheets()
+            .values_batch_update(update_request, sheet_id)
+            .doit()
+            .await;
+
+        match response {
+            Ok(my_result) => Ok(my_result.1.total_updated_cells.unwrap_or_default()),
+            Err(err) => Err(map_sheets_error(sheet_id, err)),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use super::damage_marks::DamageMarks;
+use serde::{Deserialize, Serialize};
+
+/// The state of a single box of a health track
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthBox {
+    Empty,
+    Normal,
+    Aggravated,
+    /// a mark which is neither, it is listed in the warnings of the character
+    Unknown,
+}
+
+/// The kind of damage a box is marked with
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageType {
+    #[default]
+    Normal,
+    Aggravated,
+}
+
+impl HealthBox {
+    /// reads the mark of a cell with the damage marks set at startup
+    pub fn from_mark(mark: &str) -> HealthBox {
+        let mark = mark.trim();
+
+        if mark.is_empty() {
+            HealthBox::Empty
+        } else if mark.eq_ignore_ascii_case(&DamageType::Normal.mark()) {
+            HealthBox::Normal
+        } else if mark.eq_ignore_ascii_case(&DamageType::Aggravated.mark()) {
+            HealthBox::Aggravated
+        } else {
+            HealthBox::Unknown
+        }
+    }
+
+    pub fn damage_type(&self) -> Option<DamageType> {
+        match self {
+            HealthBox::Normal => Some(DamageType::Normal),
+            HealthBox::Aggravated => Some(DamageType::Aggravated),
+            HealthBox::Empty | HealthBox::Unknown => None,
+        }
+    }
+}
+
+impl DamageType {
+    /// the mark written into the box
+    pub fn mark(&self) -> String {
+        let marks = DamageMarks::current();
+
+        match self {
+            DamageType::Normal => marks.normal.clone(),
+            DamageType::Aggravated => marks.aggravated.clone(),
+        }
+    }
+
+    pub fn health_box(&self) -> HealthBox {
+        match self {
+            DamageType::Normal => HealthBox::Normal,
+            DamageType::Aggravated => HealthBox::Aggravated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_are_read_with_the_default_marks() {
+        assert_eq!(HealthBox::from_mark(""), HealthBox::Empty);
+        assert_eq!(HealthBox::from_mark("  "), HealthBox::Empty);
+        assert_eq!(HealthBox::from_mark("x"), HealthBox::Normal);
+        assert_eq!(HealthBox::from_mark(" X "), HealthBox::Normal);
+        assert_eq!(HealthBox::from_mark("*"), HealthBox::Aggravated);
+        assert_eq!(HealthBox::from_mark("/"), HealthBox::Unknown);
+        assert_eq!(HealthBox::from_mark("3"), HealthBox::Unknown);
+    }
+
+    #[test]
+    fn only_damaged_boxes_have_a_damage_type() {
+        assert_eq!(HealthBox::Empty.damage_type(), None);
+        assert_eq!(HealthBox::Unknown.damage_type(), None);
+        assert_eq!(HealthBox::Normal.damage_type(), Some(DamageType::Normal));
+        assert_eq!(
+            DamageType::Aggravated.health_box().damage_type(),
+            Some(DamageType::Aggravated)
+        );
+    }
+
+    #[test]
+    fn written_marks_are_read_back() {
+        for damage_type in [DamageType::Normal, DamageType::Aggravated] {
+            assert_eq!(
+                HealthBox::from_mark(&damage_type.mark()),
+                damage_type.health_box()
+            );
+        }
+    }
+}
@@ -1,9 +1,45 @@
+use super::health_box::HealthBox;
 use serde::{Deserialize, Serialize};
 
+/// the number of boxes a health track has on the sheet
+pub const HEALTH_TRACK_BOXES: u8 = 5;
+
 /// HealthTrack Struct
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HealthTrack {
     pub base_value: u8,
     pub with_boni: u8,
     pub remaining: u8,
+    /// the boxes of the sheet from left to right
+    #[serde(default)]
+    pub boxes: Vec<HealthBox>,
+}
+
+impl HealthTrack {
+    /// the boxes which can be marked, boni beyond the boxes of the sheet do not count
+    pub fn capacity(&self) -> u8 {
+        self.with_boni.min(HEALTH_TRACK_BOXES)
+    }
+
+    /// the boxes marked with damage of any type
+    pub fn lost(&self) -> u8 {
+        self.boxes
+            .iter()
+            .filter(|x| x.damage_type().is_some())
+            .count() as u8
+    }
+
+    /// the same track with the given boxes marked from the left, the remaining boxes are empty
+    pub fn with_damage(&self, damage: &[HealthBox]) -> HealthTrack {
+        let mut boxes = damage.to_vec();
+        boxes.resize(HEALTH_TRACK_BOXES as usize, HealthBox::Empty);
+
+        let mut track = HealthTrack {
+            boxes,
+            ..self.clone()
+        };
+        track.remaining = self.with_boni.saturating_sub(track.lost());
+
+        track
+    }
 }
@@ -1,10 +1,265 @@
-use super::health_track::HealthTrack;
+use super::{
+    health_box::{DamageType, HealthBox},
+    health_track::HealthTrack,
+};
+use crate::config::{config_client::ConfigClient, field_name::FieldName};
+use google_sheets4::api::ValueRange;
 use serde::{Deserialize, Serialize};
 
 /// HealthTracks Struct
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HealthTracks {
     pub healthy: HealthTrack,
     pub injured: HealthTrack,
     pub incapacitated: HealthTrack,
 }
+
+impl HealthTracks {
+    /// the tracks in the order damage fills them
+    fn tracks(&self) -> [&HealthTrack; 3] {
+        [&self.healthy, &self.injured, &self.incapacitated]
+    }
+
+    /// the boxes which can be marked over all tracks
+    pub fn capacity(&self) -> u8 {
+        self.tracks().iter().map(|x| x.capacity()).sum()
+    }
+
+    /// whether a box holds a mark the gateway does not know, it would be lost when the tracks are written
+    pub fn has_unknown_marks(&self) -> bool {
+        self.tracks()
+            .iter()
+            .any(|track| track.boxes.contains(&HealthBox::Unknown))
+    }
+
+    /// the marked boxes over all tracks, in the order they are filled
+    fn damage(&self) -> Vec<HealthBox> {
+        self.tracks()
+            .iter()
+            .flat_map(|track| track.boxes.iter())
+            .filter(|x| x.damage_type().is_some())
+            .copied()
+            .collect()
+    }
+
+    /// marks more boxes, a full track spills over into the next one, damage beyond the last is dropped
+    pub fn apply_damage(&self, amount: u8, damage_type: DamageType) -> HealthTracks {
+        let mut damage = self.damage();
+        let free = (self.capacity() as usize).saturating_sub(damage.len());
+        damage.extend(vec![damage_type.health_box(); free.min(amount as usize)]);
+
+        self.with_damage(damage)
+    }
+
+    /// clears boxes of the damage type, starting with the last track damage of that type reached
+    pub fn apply_healing(&self, amount: u8, damage_type: DamageType) -> HealthTracks {
+        let mut damage = self.damage();
+        let mut left = amount;
+
+        while left > 0 {
+            match damage.iter().rposition(|x| *x == damage_type.health_box()) {
+                Some(position) => {
+                    damage.remove(position);
+                    left -= 1;
+                }
+                None => break,
+            }
+        }
+
+        self.with_damage(damage)
+    }
+
+    /// the tracks with the damage marked from the healthy track on, aggravated damage first
+    ///
+    /// Normal damage therefore always sits at the end and heals without moving aggravated marks.
+    fn with_damage(&self, mut damage: Vec<HealthBox>) -> HealthTracks {
+        damage.sort_by_key(|x| *x != HealthBox::Aggravated);
+
+        let mut left = damage.as_slice();
+        let [healthy, injured, incapacitated] = self.tracks().map(|track| {
+            let (marked, rest) = left.split_at(left.len().min(track.capacity() as usize));
+            left = rest;
+            track.with_damage(marked)
+        });
+
+        HealthTracks {
+            healthy,
+            injured,
+            incapacitated,
+        }
+    }
+
+    /// the boxes of every track with the mark of their damage type, the values of the tracks stay untouched
+    pub fn value_ranges(&self, sheet_config: &ConfigClient) -> Vec<ValueRange> {
+        [
+            (&self.healthy, FieldName::GesundheitHealthy),
+            (&self.injured, FieldName::GesundheitInjured),
+            (&self.incapacitated, FieldName::GesundheitIncapacitated),
+        ]
+        .into_iter()
+        .map(|(track, field_name)| {
+            let boxes: Vec<String> = track
+                .boxes
+                .iter()
+                .map(|x| x.damage_type().map(|x| x.mark()).unwrap_or_default())
+                .collect();
+
+            ValueRange {
+                major_dimension: Some("ROWS".to_string()),
+                range: Some(sheet_config.get_field_config(field_name).range),
+                values: Some(vec![boxes]),
+            }
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(with_boni: u8) -> HealthTrack {
+        HealthTrack {
+            base_value: with_boni,
+            with_boni,
+            remaining: with_boni,
+            boxes: vec![HealthBox::Empty; 5],
+        }
+    }
+
+    fn unharmed() -> HealthTracks {
+        HealthTracks {
+            healthy: track(3),
+            injured: track(2),
+            incapacitated: track(7),
+        }
+    }
+
+    fn lost(health: &HealthTracks) -> [u8; 3] {
+        health.tracks().map(|x| x.lost())
+    }
+
+    #[test]
+    fn damage_spills_over_into_the_next_track() {
+        let health = unharmed().apply_damage(4, DamageType::Normal);
+
+        assert_eq!(lost(&health), [3, 1, 0]);
+        assert_eq!(health.healthy.remaining, 0);
+        assert_eq!(health.injured.remaining, 1);
+        assert_eq!(health.injured.boxes[0], HealthBox::Normal);
+        assert_eq!(health.injured.boxes[1], HealthBox::Empty);
+    }
+
+    #[test]
+    fn damage_adds_to_the_marked_boxes() {
+        let health = unharmed()
+            .apply_damage(2, DamageType::Normal)
+            .apply_damage(2, DamageType::Normal);
+
+        assert_eq!(lost(&health), [3, 1, 0]);
+    }
+
+    #[test]
+    fn damage_beyond_the_last_box_is_dropped() {
+        // boni beyond the five boxes of the sheet can not be marked
+        let health = unharmed().apply_damage(20, DamageType::Normal);
+
+        assert_eq!(health.capacity(), 10);
+        assert_eq!(lost(&health), [3, 2, 5]);
+        assert_eq!(health.incapacitated.remaining, 2);
+    }
+
+    #[test]
+    fn healing_starts_with_the_last_marked_box() {
+        let health = unharmed()
+            .apply_damage(6, DamageType::Normal)
+            .apply_healing(2, DamageType::Normal);
+
+        assert_eq!(lost(&health), [3, 1, 0]);
+    }
+
+    #[test]
+    fn healing_more_than_the_damage_clears_every_box() {
+        let health = unharmed()
+            .apply_damage(4, DamageType::Normal)
+            .apply_healing(10, DamageType::Normal);
+
+        assert_eq!(lost(&health), [0, 0, 0]);
+        assert_eq!(health.injured.remaining, 2);
+    }
+
+    #[test]
+    fn aggravated_damage_is_marked_before_normal_damage() {
+        let health = unharmed()
+            .apply_damage(2, DamageType::Normal)
+            .apply_damage(2, DamageType::Aggravated);
+
+        assert_eq!(
+            health.healthy.boxes[..3],
+            [
+                HealthBox::Aggravated,
+                HealthBox::Aggravated,
+                HealthBox::Normal
+            ]
+        );
+        assert_eq!(health.injured.boxes[0], HealthBox::Normal);
+    }
+
+    #[test]
+    fn aggravated_damage_counts_against_the_capacity() {
+        let health = unharmed()
+            .apply_damage(9, DamageType::Normal)
+            .apply_damage(3, DamageType::Aggravated);
+
+        // only one box was left, the normal damage keeps its boxes
+        assert_eq!(lost(&health), [3, 2, 5]);
+        assert_eq!(health.healthy.boxes[0], HealthBox::Aggravated);
+        assert_eq!(health.healthy.boxes[1], HealthBox::Normal);
+    }
+
+    #[test]
+    fn healing_only_clears_its_damage_type() {
+        let damaged = unharmed()
+            .apply_damage(3, DamageType::Normal)
+            .apply_damage(2, DamageType::Aggravated);
+
+        let normal = damaged.apply_healing(5, DamageType::Normal);
+        let aggravated = damaged.apply_healing(1, DamageType::Aggravated);
+
+        assert_eq!(lost(&normal), [2, 0, 0]);
+        assert!(normal.damage().iter().all(|x| *x == HealthBox::Aggravated));
+        // the remaining marks move up, aggravated damage stays in front
+        assert_eq!(lost(&aggravated), [3, 1, 0]);
+        assert_eq!(
+            aggravated.damage(),
+            [
+                HealthBox::Aggravated,
+                HealthBox::Normal,
+                HealthBox::Normal,
+                HealthBox::Normal
+            ]
+        );
+    }
+
+    #[test]
+    fn every_box_is_written() {
+        let layouts =
+            crate::config::sheet_layouts::SheetLayouts::load("FieldConfig.toml", "layouts")
+                .unwrap();
+        let health = unharmed().apply_damage(4, DamageType::Normal);
+
+        let value_ranges = health.value_ranges(&layouts.default_layout());
+
+        assert_eq!(value_ranges.len(), 3);
+        assert_eq!(
+            value_ranges[1].values,
+            Some(vec![vec![
+                "x".to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new()
+            ]])
+        );
+    }
+}
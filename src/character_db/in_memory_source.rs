@@ -0,0 +1,91 @@
+use super::{character_source::CharacterSource, sheet_cells::SheetCells};
+use crate::gateway_error::GatewayError;
+use async_trait::async_trait;
+use google_sheets4::api::ValueRange;
+use std::{collections::HashMap, fs, sync::RwLock};
+
+/// Character source keeping the sheets in memory, used for tests and local development
+///
+/// A fixture file is a json object mapping sheet ids to an object of A1 ranges and their rows:
+/// `{ "my-sheet": { "D1": [["Name"]], "D9:D11": [["Focus"], ["-"], ["-"]] } }`
+#[derive(Default)]
+pub struct InMemorySource {
+    sheets: RwLock<HashMap<String, SheetCells>>,
+}
+
+impl InMemorySource {
+    pub fn new() -> InMemorySource {
+        InMemorySource::default()
+    }
+
+    pub fn from_fixture_file(path: &str) -> Result<InMemorySource, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("could not read fixture {}: {}", path, err))?;
+        let fixture: HashMap<String, HashMap<String, Vec<Vec<String>>>> =
+            serde_json::from_str(&content)
+                .map_err(|err| format!("could not parse fixture {}: {}", path, err))?;
+
+        let source = InMemorySource::new();
+
+        for (sheet_id, ranges) in fixture {
+            let value_ranges = ranges
+                .into_iter()
+                .map(|(range, values)| ValueRange {
+                    major_dimension: Some("ROWS".to_string()),
+                    range: Some(range),
+                    values: Some(values),
+                })
+                .collect();
+
+            source.insert_ranges(&sheet_id, value_ranges)?;
+        }
+
+        Ok(source)
+    }
+
+    /// creates the sheet if needed and writes the passed in ranges into it
+    pub fn insert_ranges(&self, sheet_id: &str, data: Vec<ValueRange>) -> Result<i32, String> {
+        let mut sheets = self.sheets.write().unwrap();
+        let cells = sheets.entry(sheet_id.to_string()).or_default();
+        let mut updated_cells = 0;
+
+        for value_range in data {
+            updated_cells += cells.write_range(&value_range)?;
+        }
+
+        Ok(updated_cells)
+    }
+}
+
+#[async_trait]
+impl CharacterSource for InMemorySource {
+    async fn load_ranges(
+        &self,
+        sheet_id: &str,
+        ranges: &[String],
+    ) -> Result<Vec<ValueRange>, GatewayError> {
+        let sheets = self.sheets.read().unwrap();
+        let cells = sheets
+            .get(sheet_id)
+            .ok_or_else(|| GatewayError::SheetNotFound(sheet_id.to_string()))?;
+
+        ranges
+            .iter()
+            .map(|range| cells.read_range(range))
+            .collect::<Result<Vec<ValueRange>, String>>()
+            .map_err(GatewayError::InvalidRange)
+    }
+
+    async fn update_ranges(
+        &self,
+        sheet_id: &str,
+        data: Vec<ValueRange>,
+    ) -> Result<i32, GatewayError> {
+        if !self.sheets.read().unwrap().contains_key(sheet_id) {
+            return Err(GatewayError::SheetNotFound(sheet_id.to_string()));
+        }
+
+        self.insert_ranges(sheet_id, data)
+            .map_err(GatewayError::InvalidRange)
+    }
+}
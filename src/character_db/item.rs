@@ -11,3 +11,21 @@ pub struct Item {
     pub additional_trait: String,
     pub additional_trait_description: String,
 }
+
+/// Input for updating an item, traits left out are cleared
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ItemUpdateInput {
+    pub name: String,
+    #[serde(default)]
+    pub trait_1: String,
+    #[serde(default)]
+    pub trait_1_description: String,
+    #[serde(default)]
+    pub trait_2: String,
+    #[serde(default)]
+    pub trait_2_description: String,
+    #[serde(default)]
+    pub additional_trait: String,
+    #[serde(default)]
+    pub additional_trait_description: String,
+}
@@ -9,7 +9,7 @@ pub struct Merit {
 }
 
 /// Input for updating merits
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MeritUpdateInput {
     pub name: String,
     pub merit_type: String,
@@ -5,19 +5,34 @@ pub mod battle_base_information;
 pub mod battle_defense_information;
 pub mod battle_information;
 pub mod battle_offense_information;
+pub mod character_diff;
+pub mod character_patch;
+pub mod character_source;
+pub mod character_update;
+pub mod damage_marks;
 pub mod discipline;
 pub mod experience_information;
 pub mod flaw;
+pub mod google_sheets_source;
+pub mod health_box;
 pub mod health_track;
 pub mod health_tracks;
+pub mod in_memory_source;
 pub mod item;
 pub mod merit;
 pub mod morality;
 pub mod name_value;
+pub mod parse_diagnostic;
 pub mod physical_defense_pool;
 pub mod player_character;
 pub mod player_character_client;
 pub mod powers;
+pub mod range_change;
 pub mod ritual;
+pub mod sheet_cells;
+pub mod sheet_parser;
+pub mod sheet_range;
 pub mod skill;
 pub mod skills;
+pub mod workbook_source;
+pub mod workbook_writer;
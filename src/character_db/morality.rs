@@ -8,7 +8,7 @@ pub struct Morality {
 }
 
 /// Input for updating the morality section
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MoralityUpdateInput {
     pub name: Option<String>,
     pub value: Option<u8>,
@@ -6,3 +6,9 @@ pub struct NameValue {
     pub name: String,
     pub value: u8,
 }
+
+/// Input for selecting a skill, attribute or wildcard by name, the value follows from the sheet
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NameUpdateInput {
+    pub name: String,
+}
@@ -0,0 +1,11 @@
+use crate::config::field_name::FieldName;
+use serde::{Deserialize, Serialize};
+
+/// A cell which could not be read as expected, the field falls back to its default value
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ParseDiagnostic {
+    pub field_name: FieldName,
+    pub cell: String,
+    pub value: String,
+    pub expected: String,
+}
@@ -4,29 +4,33 @@ use super::{
     background::{Background, BackgroundUpdateInput},
     battle_base_information::BattleBaseInformation,
     battle_defense_information::BattleDefenseInformation,
-    battle_information::BattleInformation,
-    battle_offense_information::BattleOffenseInformation,
-    discipline::{Discipline, DisciplineUpdateInput},
+    battle_information::{BattleInformation, BattleInformationUpdateInput},
+    battle_offense_information::BattleOffenseUpdateInput,
+    discipline::DisciplineUpdateInput,
     experience_information::{ExperienceInformation, ExperienceInformationUpdateInput},
     flaw::{Flaw, FlawUpdateInput},
-    health_track::HealthTrack,
     health_tracks::HealthTracks,
-    item::Item,
+    item::{Item, ItemUpdateInput},
     merit::{Merit, MeritUpdateInput},
     morality::{Morality, MoralityUpdateInput},
-    name_value::NameValue,
+    parse_diagnostic::ParseDiagnostic,
     physical_defense_pool::PhysicalDefensePool,
     powers::{Powers, PowersUpdateInput},
     ritual::{Ritual, RitualUpdateInput},
+    sheet_parser::SheetParser,
+    sheet_range::SheetRange,
     skill::Skill,
     skills::{Skills, SkillsUpdateInput},
 };
-use crate::config::{config_client::ConfigClient, field_name::FieldName};
+use crate::{
+    api_key_scope::ApiKeyScope,
+    config::{config_client::ConfigClient, field_name::FieldName},
+    gateway_error::GatewayError,
+};
 use either::Either::{self, Left, Right};
 use google_sheets4::api::ValueRange;
-use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr};
 
 /// the output to the character_data handler
 #[derive(Serialize, Deserialize, Debug)]
@@ -52,10 +56,12 @@ pub struct PlayerCharacter {
     pub battle_information: BattleInformation,
     pub rituals: Vec<Ritual>,
     pub items: Vec<Item>,
+    #[serde(default)]
+    pub warnings: Vec<ParseDiagnostic>,
 }
 
 /// the input for updating a character
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PlayerCharacterUpdateInput {
     pub character_name: Option<String>,
     pub player_name: Option<String>,
@@ -71,158 +77,135 @@ pub struct PlayerCharacterUpdateInput {
     pub flaws: Option<Vec<FlawUpdateInput>>,
     pub backgrounds: Option<Vec<BackgroundUpdateInput>>,
     pub experience_information: Option<ExperienceInformationUpdateInput>,
+    pub battle_information: Option<BattleInformationUpdateInput>,
     pub rituals: Option<Vec<RitualUpdateInput>>,
+    pub items: Option<Vec<ItemUpdateInput>>,
 }
 
-impl From<Vec<ValueRange>> for PlayerCharacter {
-    fn from(data: Vec<ValueRange>) -> Self {
-        let config = ConfigClient::new();
-
-        let character_name = get_value(&data, FieldName::CharacterName, &config);
-        let player_name = get_value(&data, FieldName::PlayerName, &config);
-        let version_sheet = get_value(&data, FieldName::VersionSheet, &config);
-        let archetype = get_value(&data, FieldName::Archetype, &config);
-        let generation_raw = get_value::<String>(&data, FieldName::Generation, &config);
+impl PlayerCharacter {
+    /// parses the sheet response, unreadable cells are listed in the warnings
+    pub fn parse(
+        data: &[ValueRange],
+        sheet_config: &ConfigClient,
+    ) -> Result<PlayerCharacter, GatewayError> {
+        let mut parser = SheetParser::new(data, sheet_config);
+
+        let character_name = parser.get_value(FieldName::CharacterName)?;
+        let player_name = parser.get_value(FieldName::PlayerName)?;
+        let version_sheet = parser.get_value(FieldName::VersionSheet)?;
+        let archetype = parser.get_value(FieldName::Archetype)?;
+        let generation_raw = parser.get_value::<String>(FieldName::Generation)?;
         let generation: Either<u8, String> = if let Ok(parsed) = generation_raw.parse() {
             Left(parsed)
         } else {
             Right(generation_raw)
         };
-        let clan = get_value(&data, FieldName::Clan, &config);
-        let blood_per_turn = get_value::<u8>(&data, FieldName::BlutvorratBlutProRunde, &config);
-        let blood_pool = get_value::<u8>(&data, FieldName::BlutvorratBlutpool, &config);
-        let attribut_physical_value =
-            get_value::<u8>(&data, FieldName::AttributKörperlichWert, &config);
-        let attribut_social_value = get_value::<u8>(&data, FieldName::AttributSozialWert, &config);
-        let attribut_mental_value = get_value::<u8>(&data, FieldName::AttributMentalWert, &config);
-        let attribut_physical_foci =
-            get_value_vec(&data, FieldName::AttributKörperlicheFoki, &config);
-        let attribut_social_foci = get_value_vec(&data, FieldName::AttributSozialeFoki, &config);
-        let attribut_mental_foci = get_value_vec(&data, FieldName::AttributMentaleFoki, &config);
-        let academics_value = get_value::<u8>(&data, FieldName::SkillAkademischesWissen, &config);
-        let academics_foci = get_skill_specialization(
-            &data,
-            FieldName::SkillAkademischesWissenSpezialisierung,
-            &config,
-        );
-        let subterfuge_value = get_value::<u8>(&data, FieldName::SkillAusfluechte, &config);
-        let dodge_value = get_value::<u8>(&data, FieldName::SkillAusweichen, &config);
-        let computer_value = get_value::<u8>(&data, FieldName::SkillComputer, &config);
-        let intimidation_value = get_value::<u8>(&data, FieldName::SkillEinschüchtern, &config);
-        let empathy_value = get_value::<u8>(&data, FieldName::SkillEmpathie, &config);
-        let drive_value = get_value::<u8>(&data, FieldName::SkillFahren, &config);
-        let leadership_value = get_value::<u8>(&data, FieldName::SkillFührungsqualitäten, &config);
-        let brawl_value = get_value::<u8>(&data, FieldName::SkillHandgemenge, &config);
-        let craft_a_value = get_value::<u8>(&data, FieldName::SkillHandwerkA, &config);
+        let clan = parser.get_value(FieldName::Clan)?;
+        let blood_per_turn = parser.get_value::<u8>(FieldName::BlutvorratBlutProRunde)?;
+        let blood_pool = parser.get_value::<u8>(FieldName::BlutvorratBlutpool)?;
+        let attribut_physical_value = parser.get_value::<u8>(FieldName::AttributKörperlichWert)?;
+        let attribut_social_value = parser.get_value::<u8>(FieldName::AttributSozialWert)?;
+        let attribut_mental_value = parser.get_value::<u8>(FieldName::AttributMentalWert)?;
+        let attribut_physical_foci = parser.get_value_vec(FieldName::AttributKörperlicheFoki)?;
+        let attribut_social_foci = parser.get_value_vec(FieldName::AttributSozialeFoki)?;
+        let attribut_mental_foci = parser.get_value_vec(FieldName::AttributMentaleFoki)?;
+        let academics_value = parser.get_value::<u8>(FieldName::SkillAkademischesWissen)?;
+        let academics_foci =
+            parser.get_skill_specialization(FieldName::SkillAkademischesWissenSpezialisierung)?;
+        let subterfuge_value = parser.get_value::<u8>(FieldName::SkillAusfluechte)?;
+        let dodge_value = parser.get_value::<u8>(FieldName::SkillAusweichen)?;
+        let computer_value = parser.get_value::<u8>(FieldName::SkillComputer)?;
+        let intimidation_value = parser.get_value::<u8>(FieldName::SkillEinschüchtern)?;
+        let empathy_value = parser.get_value::<u8>(FieldName::SkillEmpathie)?;
+        let drive_value = parser.get_value::<u8>(FieldName::SkillFahren)?;
+        let leadership_value = parser.get_value::<u8>(FieldName::SkillFührungsqualitäten)?;
+        let brawl_value = parser.get_value::<u8>(FieldName::SkillHandgemenge)?;
+        let craft_a_value = parser.get_value::<u8>(FieldName::SkillHandwerkA)?;
         let craft_a_foci =
-            get_skill_specialization(&data, FieldName::SkillHandwerkASpezialisierung, &config);
-        let craft_b_value = get_value::<u8>(&data, FieldName::SkillHandwerkB, &config);
+            parser.get_skill_specialization(FieldName::SkillHandwerkASpezialisierung)?;
+        let craft_b_value = parser.get_value::<u8>(FieldName::SkillHandwerkB)?;
         let craft_b_foci =
-            get_skill_specialization(&data, FieldName::SkillHandwerkBSpezialisierung, &config);
-        let stealth_value = get_value::<u8>(&data, FieldName::SkillHeimlichkeit, &config);
-        let linguistics_value = get_value::<u8>(&data, FieldName::SkillLinguistik, &config);
+            parser.get_skill_specialization(FieldName::SkillHandwerkBSpezialisierung)?;
+        let stealth_value = parser.get_value::<u8>(FieldName::SkillHeimlichkeit)?;
+        let linguistics_value = parser.get_value::<u8>(FieldName::SkillLinguistik)?;
         let linguistics_foci =
-            get_skill_specialization(&data, FieldName::SkillLinguistikSpezialisierung, &config);
-        let awareness_value = get_value::<u8>(&data, FieldName::SkillMagiegespür, &config);
-        let medicine_value = get_value::<u8>(&data, FieldName::SkillMedizin, &config);
-        let investigation_value = get_value::<u8>(&data, FieldName::SkillNachforschungen, &config);
-        let melee_value = get_value::<u8>(&data, FieldName::SkillNahkampf, &config);
-        let science_a_value = get_value::<u8>(&data, FieldName::SkillNaturwissenschaftenA, &config);
-        let science_a_foci = get_skill_specialization(
-            &data,
-            FieldName::SkillNaturwissenschaftenASpezialisierung,
-            &config,
-        );
-        let science_b_value = get_value::<u8>(&data, FieldName::SkillNaturwissenschaftenB, &config);
-        let science_b_foci = get_skill_specialization(
-            &data,
-            FieldName::SkillNaturwissenschaftenBSpezialisierung,
-            &config,
-        );
-        let occult_value = get_value::<u8>(&data, FieldName::SkillOkkultismus, &config);
-        let firearms_value = get_value::<u8>(&data, FieldName::SkillSchusswaffen, &config);
-        let security_value = get_value::<u8>(&data, FieldName::SkillSicherheit, &config);
-        let athletics_value = get_value::<u8>(&data, FieldName::SkillSportlichkeit, &config);
-        let streetwise_value = get_value::<u8>(&data, FieldName::SkillSzenekenntnis, &config);
-        let animal_ken_value = get_value::<u8>(&data, FieldName::SkillTierkunde, &config);
-        let survival_value = get_value::<u8>(&data, FieldName::SkillÜberleben, &config);
-        let performance_a_value = get_value::<u8>(&data, FieldName::SkillVortragA, &config);
+            parser.get_skill_specialization(FieldName::SkillLinguistikSpezialisierung)?;
+        let awareness_value = parser.get_value::<u8>(FieldName::SkillMagiegespür)?;
+        let medicine_value = parser.get_value::<u8>(FieldName::SkillMedizin)?;
+        let investigation_value = parser.get_value::<u8>(FieldName::SkillNachforschungen)?;
+        let melee_value = parser.get_value::<u8>(FieldName::SkillNahkampf)?;
+        let science_a_value = parser.get_value::<u8>(FieldName::SkillNaturwissenschaftenA)?;
+        let science_a_foci =
+            parser.get_skill_specialization(FieldName::SkillNaturwissenschaftenASpezialisierung)?;
+        let science_b_value = parser.get_value::<u8>(FieldName::SkillNaturwissenschaftenB)?;
+        let science_b_foci =
+            parser.get_skill_specialization(FieldName::SkillNaturwissenschaftenBSpezialisierung)?;
+        let occult_value = parser.get_value::<u8>(FieldName::SkillOkkultismus)?;
+        let firearms_value = parser.get_value::<u8>(FieldName::SkillSchusswaffen)?;
+        let security_value = parser.get_value::<u8>(FieldName::SkillSicherheit)?;
+        let athletics_value = parser.get_value::<u8>(FieldName::SkillSportlichkeit)?;
+        let streetwise_value = parser.get_value::<u8>(FieldName::SkillSzenekenntnis)?;
+        let animal_ken_value = parser.get_value::<u8>(FieldName::SkillTierkunde)?;
+        let survival_value = parser.get_value::<u8>(FieldName::SkillÜberleben)?;
+        let performance_a_value = parser.get_value::<u8>(FieldName::SkillVortragA)?;
         let performance_a_foci =
-            get_skill_specialization(&data, FieldName::SkillVortragASpezialisierung, &config);
-        let performance_b_value = get_value(&data, FieldName::SkillVortragB, &config);
+            parser.get_skill_specialization(FieldName::SkillVortragASpezialisierung)?;
+        let performance_b_value = parser.get_value(FieldName::SkillVortragB)?;
         let performance_b_foci =
-            get_skill_specialization(&data, FieldName::SkillVortragBSpezialisierung, &config);
-        let lore_value = get_value::<u8>(&data, FieldName::SkillÜbernatürlichesWissen, &config);
-        let lore_foci = get_skill_specialization(
-            &data,
-            FieldName::SkillÜbernatürlichesWissenSpezialisierung,
-            &config,
-        );
-        let in_clan_disciplines = get_disciplines(&data, FieldName::InClanDisziplinen, &config);
-        let out_of_clan_disciplines =
-            get_disciplines(&data, FieldName::OutOfClanDisziplinen, &config);
-        let techniques = get_value_vec(&data, FieldName::Techniken, &config);
-        let in_clan_elder_powers = get_value_vec(&data, FieldName::InClanAhnenkräfte, &config);
-        let out_of_clan_elder_powers =
-            get_value_vec(&data, FieldName::OutOfClanAhnenkräfte, &config);
-        let morality_name = get_value(&data, FieldName::MoralvorstellungName, &config);
-        let morality_value = get_value::<u8>(&data, FieldName::MoralvorstellungWert, &config);
-        let faction_name = get_value(&data, FieldName::FraktionName, &config);
-        let merits_and_flaws =
-            extract_value_from_vec(&data, FieldName::MeritsFlaws, &config).unwrap();
-        let merits = extract_merits(&merits_and_flaws);
-        let flaws = extract_flaws(&merits_and_flaws);
-        let backgrounds = get_backgrounds(&data, FieldName::Backgrounds, &config);
+            parser.get_skill_specialization(FieldName::SkillVortragBSpezialisierung)?;
+        let lore_value = parser.get_value::<u8>(FieldName::SkillÜbernatürlichesWissen)?;
+        let lore_foci = parser
+            .get_skill_specialization(FieldName::SkillÜbernatürlichesWissenSpezialisierung)?;
+        let in_clan_disciplines = parser.get_disciplines(FieldName::InClanDisziplinen)?;
+        let out_of_clan_disciplines = parser.get_disciplines(FieldName::OutOfClanDisziplinen)?;
+        let techniques = parser.get_value_vec(FieldName::Techniken)?;
+        let in_clan_elder_powers = parser.get_value_vec(FieldName::InClanAhnenkräfte)?;
+        let out_of_clan_elder_powers = parser.get_value_vec(FieldName::OutOfClanAhnenkräfte)?;
+        let morality_name = parser.get_value(FieldName::MoralvorstellungName)?;
+        let morality_value = parser.get_value::<u8>(FieldName::MoralvorstellungWert)?;
+        let faction_name = parser.get_value(FieldName::FraktionName)?;
+        let merits = parser.get_merits(FieldName::MeritsFlaws)?;
+        let flaws = parser.get_flaws(FieldName::MeritsFlaws)?;
+        let backgrounds = parser.get_backgrounds(FieldName::Backgrounds)?;
         let experience_start_value =
-            get_value::<u8>(&data, FieldName::ErfahrungspunkteStartpunkte, &config);
+            parser.get_value::<u8>(FieldName::ErfahrungspunkteStartpunkte)?;
         let experience_spent_total =
-            get_value::<u16>(&data, FieldName::ErfahrungspunkteGesamtAusgegeben, &config);
+            parser.get_value::<u16>(FieldName::ErfahrungspunkteGesamtAusgegeben)?;
         let experience_remaining =
-            get_value::<i16>(&data, FieldName::ErfahrungspunkteAktuellFrei, &config);
+            parser.get_value::<i16>(FieldName::ErfahrungspunkteAktuellFrei)?;
         let experience_received_total =
-            get_value::<u8>(&data, FieldName::ErfahrungspunkteGesamtErhalten, &config);
-        let initiative = get_value::<u8>(&data, FieldName::Initiative, &config);
+            parser.get_value::<u8>(FieldName::ErfahrungspunkteGesamtErhalten)?;
+        let initiative = parser.get_value::<u8>(FieldName::Initiative)?;
         let initiative_with_celerity =
-            get_value::<u8>(&data, FieldName::InitiativeGeschwindigkeit, &config);
-        let health_healthy_track = get_health_track(&data, FieldName::GesundheitHealthy, &config);
-        let health_injured_track = get_health_track(&data, FieldName::GesundheitInjured, &config);
+            parser.get_value::<u8>(FieldName::InitiativeGeschwindigkeit)?;
+        let health_healthy_track = parser.get_health_track(FieldName::GesundheitHealthy)?;
+        let health_injured_track = parser.get_health_track(FieldName::GesundheitInjured)?;
         let health_incapacitated_track =
-            get_health_track(&data, FieldName::GesundheitIncapacitated, &config);
+            parser.get_health_track(FieldName::GesundheitIncapacitated)?;
         let physical_defense_base =
-            get_value::<u8>(&data, FieldName::VerteidigungKörperlichRegulär, &config);
-        let physical_defense_with_celerity = get_value::<u8>(
-            &data,
-            FieldName::VerteidigungKörperlichMitGeschwindigkeit,
-            &config,
-        );
-        let physical_defense_frenzy_modifier = get_value::<i8>(
-            &data,
-            FieldName::VerteidigungKörperlichRasereiModifier,
-            &config,
-        );
-        let physical_defense_on_the_ground_closer_than_3_meters = get_value::<i8>(
-            &data,
-            FieldName::VerteidigungKörperlichAmBodenGegnerNäherAls3Meter,
-            &config,
-        );
-        let physical_defense_on_the_ground_further_than_3_meters = get_value::<u8>(
-            &data,
-            FieldName::VerteidigungKörperlichAmBodenGegnerMindestens3MeterWeg,
-            &config,
-        );
+            parser.get_value::<u8>(FieldName::VerteidigungKörperlichRegulär)?;
+        let physical_defense_with_celerity =
+            parser.get_value::<u8>(FieldName::VerteidigungKörperlichMitGeschwindigkeit)?;
+        let physical_defense_frenzy_modifier =
+            parser.get_value::<i8>(FieldName::VerteidigungKörperlichRasereiModifier)?;
+        let physical_defense_on_the_ground_closer_than_3_meters =
+            parser.get_value::<i8>(FieldName::VerteidigungKörperlichAmBodenGegnerNäherAls3Meter)?;
+        let physical_defense_on_the_ground_further_than_3_meters = parser
+            .get_value::<u8>(FieldName::VerteidigungKörperlichAmBodenGegnerMindestens3MeterWeg)?;
         let physical_defense_special =
-            get_value::<i8>(&data, FieldName::VerteidigungKörperlichSpecial, &config);
+            parser.get_value::<i8>(FieldName::VerteidigungKörperlichSpecial)?;
         let social_defense_pool =
-            get_non_physical_defense_pool(&data, FieldName::VerteidigungSozial, &config);
+            parser.get_non_physical_defense_pool(FieldName::VerteidigungSozial)?;
         let mental_defense_pool =
-            get_non_physical_defense_pool(&data, FieldName::VerteidigungMental, &config);
-        let offense_pools = get_attack_pools(&data, FieldName::AngriffsPools, &config);
-        let rituals = get_rituals(&data, FieldName::Rituale, &config);
-        let items = get_items(&data, FieldName::Items, &config);
+            parser.get_non_physical_defense_pool(FieldName::VerteidigungMental)?;
+        let offense_pools = parser.get_attack_pools(FieldName::AngriffsPools)?;
+        let rituals = parser.get_rituals(FieldName::Rituale)?;
+        let items = parser.get_items(FieldName::Items)?;
         let valid = !experience_spent_total.gt(&900_u16);
+        let warnings = parser.into_diagnostics();
 
         // creating the result struct
-        PlayerCharacter {
+        Ok(PlayerCharacter {
             character_name,
             player_name,
             version_sheet,
@@ -413,30 +396,59 @@ impl From<Vec<ValueRange>> for PlayerCharacter {
             },
             rituals,
             items,
-        }
+            warnings,
+        })
     }
 }
 //
 
-#[allow(clippy::from_over_into)]
-impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
-    fn into(self) -> Vec<ValueRange> {
+impl PlayerCharacterUpdateInput {
+    /// converts the update into the value ranges to write to the sheet
+    ///
+    /// Fails without producing any write if a range is computed, not writable or reserved for storytellers,
+    /// or if a list has more rows than its range.
+    pub fn into_value_ranges(
+        self,
+        sheet_config: &ConfigClient,
+        scope: ApiKeyScope,
+    ) -> Result<Vec<ValueRange>, GatewayError> {
+        check_length(
+            self.items.as_ref(),
+            "items",
+            sheet_config.get_field_config(FieldName::Items).range_length,
+        )?;
+        check_length(
+            self.battle_information
+                .as_ref()
+                .and_then(|x| x.offense.as_ref()),
+            "attack pools",
+            sheet_config
+                .get_field_config(FieldName::AngriffsPoolsFertigkeit)
+                .range_length,
+        )?;
+
+        let value_ranges = self.value_ranges(sheet_config);
+        check_value_ranges(&value_ranges, sheet_config, scope)?;
+
+        Ok(value_ranges)
+    }
+
+    fn value_ranges(self, sheet_config: &ConfigClient) -> Vec<ValueRange> {
         let mut result: Vec<ValueRange> = vec![];
-        let config = ConfigClient::new();
 
         // Converting the data
         let character_name_option =
-            set_value(self.character_name, FieldName::CharacterName, &config);
-        let player_name_option = set_value(self.player_name, FieldName::PlayerName, &config);
-        let archetype_option = set_value(self.archetype, FieldName::Archetype, &config);
+            set_value(self.character_name, FieldName::CharacterName, sheet_config);
+        let player_name_option = set_value(self.player_name, FieldName::PlayerName, sheet_config);
+        let archetype_option = set_value(self.archetype, FieldName::Archetype, sheet_config);
         let generation_option = match self.generation {
             Some(value) => match value {
-                Left(a_number) => set_value(Some(a_number), FieldName::Generation, &config),
-                Right(a_string) => set_value(Some(a_string), FieldName::Generation, &config),
+                Left(a_number) => set_value(Some(a_number), FieldName::Generation, sheet_config),
+                Right(a_string) => set_value(Some(a_string), FieldName::Generation, sheet_config),
             },
             None => None,
         };
-        let clan_option = set_value(self.clan, FieldName::Clan, &config);
+        let clan_option = set_value(self.clan, FieldName::Clan, sheet_config);
 
         let mut attribute_physical_value_option: Option<ValueRange> = None;
         let mut attribute_social_value_option: Option<ValueRange> = None;
@@ -451,13 +463,13 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
                 attribute_physical_value_option = set_value(
                     physical_attribute.value,
                     FieldName::AttributKörperlichWert,
-                    &config,
+                    sheet_config,
                 );
 
                 attribute_physical_specialization_option = set_value_vec(
                     physical_attribute.foci,
                     FieldName::AttributKörperlicheFoki,
-                    &config,
+                    sheet_config,
                     None,
                 );
             }
@@ -466,13 +478,13 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
                 attribute_social_value_option = set_value(
                     social_attribute.value,
                     FieldName::AttributSozialWert,
-                    &config,
+                    sheet_config,
                 );
 
                 attribute_social_specialization_option = set_value_vec(
                     social_attribute.foci,
                     FieldName::AttributSozialeFoki,
-                    &config,
+                    sheet_config,
                     None,
                 );
             }
@@ -481,13 +493,13 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
                 attribute_mental_value_option = set_value(
                     mental_attribute.value,
                     FieldName::AttributMentalWert,
-                    &config,
+                    sheet_config,
                 );
 
                 attribute_mental_specialization_option = set_value_vec(
                     mental_attribute.foci,
                     FieldName::AttributMentaleFoki,
-                    &config,
+                    sheet_config,
                     None,
                 );
             }
@@ -534,89 +546,96 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
 
         if let Some(skills) = self.skills {
             if let Some(academics) = skills.academics {
-                skill_academics_value_option =
-                    set_value(academics.value, FieldName::SkillAkademischesWissen, &config);
+                skill_academics_value_option = set_value(
+                    academics.value,
+                    FieldName::SkillAkademischesWissen,
+                    sheet_config,
+                );
                 skill_academics_specialization_option = set_skill_specialization(
                     academics.foci,
                     FieldName::SkillAkademischesWissenSpezialisierung,
-                    &config,
+                    sheet_config,
                 );
             }
 
             if let Some(animal_ken) = skills.animal_ken {
                 skill_animal_ken_value_option =
-                    set_value(animal_ken.value, FieldName::SkillTierkunde, &config)
+                    set_value(animal_ken.value, FieldName::SkillTierkunde, sheet_config)
             }
 
             if let Some(athletics) = skills.athletics {
                 skill_athletics_value_option =
-                    set_value(athletics.value, FieldName::SkillSportlichkeit, &config)
+                    set_value(athletics.value, FieldName::SkillSportlichkeit, sheet_config)
             }
 
             if let Some(awareness) = skills.awareness {
                 skill_awareness_value_option =
-                    set_value(awareness.value, FieldName::SkillMagiegespür, &config)
+                    set_value(awareness.value, FieldName::SkillMagiegespür, sheet_config)
             }
 
             if let Some(brawl) = skills.brawl {
                 skill_brawl_value_option =
-                    set_value(brawl.value, FieldName::SkillHandgemenge, &config)
+                    set_value(brawl.value, FieldName::SkillHandgemenge, sheet_config)
             }
 
             if let Some(computer) = skills.computer {
                 skill_computer_value_option =
-                    set_value(computer.value, FieldName::SkillComputer, &config)
+                    set_value(computer.value, FieldName::SkillComputer, sheet_config)
             }
 
             if let Some(craft_a) = skills.craft_a {
                 skill_craft_a_value_option =
-                    set_value(craft_a.value, FieldName::SkillHandwerkA, &config);
+                    set_value(craft_a.value, FieldName::SkillHandwerkA, sheet_config);
                 skill_craft_a_specialization_option = set_skill_specialization(
                     craft_a.foci,
                     FieldName::SkillHandwerkASpezialisierung,
-                    &config,
+                    sheet_config,
                 );
             }
 
             if let Some(craft_b) = skills.craft_b {
                 skill_craft_b_value_option =
-                    set_value(craft_b.value, FieldName::SkillHandwerkB, &config);
+                    set_value(craft_b.value, FieldName::SkillHandwerkB, sheet_config);
                 skill_craft_b_specialization_option = set_skill_specialization(
                     craft_b.foci,
                     FieldName::SkillHandwerkBSpezialisierung,
-                    &config,
+                    sheet_config,
                 );
             }
 
             if let Some(dodge) = skills.dodge {
                 skill_dodge_value_option =
-                    set_value(dodge.value, FieldName::SkillAusweichen, &config)
+                    set_value(dodge.value, FieldName::SkillAusweichen, sheet_config)
             }
 
             if let Some(drive) = skills.drive {
-                skill_drive_value_option = set_value(drive.value, FieldName::SkillFahren, &config)
+                skill_drive_value_option =
+                    set_value(drive.value, FieldName::SkillFahren, sheet_config)
             }
 
             if let Some(empathy) = skills.empathy {
                 skill_empathy_value_option =
-                    set_value(empathy.value, FieldName::SkillEmpathie, &config)
+                    set_value(empathy.value, FieldName::SkillEmpathie, sheet_config)
             }
 
             if let Some(firearms) = skills.firearms {
                 skill_firearms_value_option =
-                    set_value(firearms.value, FieldName::SkillSchusswaffen, &config)
+                    set_value(firearms.value, FieldName::SkillSchusswaffen, sheet_config)
             }
 
             if let Some(intimidation) = skills.intimidation {
-                skill_intimidation_value_option =
-                    set_value(intimidation.value, FieldName::SkillEinschüchtern, &config)
+                skill_intimidation_value_option = set_value(
+                    intimidation.value,
+                    FieldName::SkillEinschüchtern,
+                    sheet_config,
+                )
             }
 
             if let Some(investigation) = skills.investigation {
                 skill_investigation_value_option = set_value(
                     investigation.value,
                     FieldName::SkillNachforschungen,
-                    &config,
+                    sheet_config,
                 )
             }
 
@@ -624,79 +643,83 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
                 skill_leadership_value_option = set_value(
                     leadership.value,
                     FieldName::SkillFührungsqualitäten,
-                    &config,
+                    sheet_config,
                 )
             }
 
             if let Some(linguistics) = skills.linguistics {
                 skill_linguistics_value_option =
-                    set_value(linguistics.value, FieldName::SkillLinguistik, &config);
+                    set_value(linguistics.value, FieldName::SkillLinguistik, sheet_config);
                 skill_linguistics_specialization_option = set_skill_specialization(
                     linguistics.foci,
                     FieldName::SkillLinguistikSpezialisierung,
-                    &config,
+                    sheet_config,
                 );
             }
 
             if let Some(lore) = skills.lore {
-                skill_lore_value_option =
-                    set_value(lore.value, FieldName::SkillÜbernatürlichesWissen, &config);
+                skill_lore_value_option = set_value(
+                    lore.value,
+                    FieldName::SkillÜbernatürlichesWissen,
+                    sheet_config,
+                );
                 skill_lore_specialization_option = set_skill_specialization(
                     lore.foci,
                     FieldName::SkillÜbernatürlichesWissenSpezialisierung,
-                    &config,
+                    sheet_config,
                 );
             }
 
             if let Some(medicine) = skills.medicine {
                 skill_medicine_value_option =
-                    set_value(medicine.value, FieldName::SkillMedizin, &config)
+                    set_value(medicine.value, FieldName::SkillMedizin, sheet_config)
             }
 
             if let Some(melee) = skills.melee {
-                skill_melee_value_option = set_value(melee.value, FieldName::SkillNahkampf, &config)
+                skill_melee_value_option =
+                    set_value(melee.value, FieldName::SkillNahkampf, sheet_config)
             }
 
             if let Some(occult) = skills.occult {
                 skill_occult_value_option =
-                    set_value(occult.value, FieldName::SkillOkkultismus, &config)
+                    set_value(occult.value, FieldName::SkillOkkultismus, sheet_config)
             }
 
             if let Some(performance_a) = skills.performance_a {
                 skill_performance_a_value_option =
-                    set_value(performance_a.value, FieldName::SkillVortragA, &config);
+                    set_value(performance_a.value, FieldName::SkillVortragA, sheet_config);
                 skill_performance_a_specialization_option = set_skill_specialization(
                     performance_a.foci,
                     FieldName::SkillVortragASpezialisierung,
-                    &config,
+                    sheet_config,
                 );
             }
 
             if let Some(performance_b) = skills.performance_b {
                 skill_performance_b_value_option =
-                    set_value(performance_b.value, FieldName::SkillVortragB, &config);
+                    set_value(performance_b.value, FieldName::SkillVortragB, sheet_config);
                 skill_performance_b_specialization_option = set_skill_specialization(
                     performance_b.foci,
                     FieldName::SkillVortragBSpezialisierung,
-                    &config,
+                    sheet_config,
                 );
             }
 
             if let Some(security) = skills.security {
                 skill_security_option =
-                    set_value(security.value, FieldName::SkillSicherheit, &config)
+                    set_value(security.value, FieldName::SkillSicherheit, sheet_config)
             }
 
             if let Some(science_a) = skills.science_a {
                 skill_science_a_value_option = set_value(
                     science_a.value,
                     FieldName::SkillNaturwissenschaftenA,
-                    &config,
+                    sheet_config,
                 );
                 skill_science_a_specialization_option = set_skill_specialization(
                     science_a.foci,
                     FieldName::SkillNaturwissenschaftenASpezialisierung,
-                    &config,
+                    sheet_config,
                 );
             }
 
@@ -704,33 +727,36 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
                 skill_science_b_value_option = set_value(
                     science_b.value,
                     FieldName::SkillNaturwissenschaftenB,
-                    &config,
+                    sheet_config,
                 );
                 skill_science_b_specialization_option = set_skill_specialization(
                     science_b.foci,
                     FieldName::SkillNaturwissenschaftenBSpezialisierung,
-                    &config,
+                    sheet_config,
                 );
             }
 
             if let Some(stealth) = skills.stealth {
                 skill_stealth_value_option =
-                    set_value(stealth.value, FieldName::SkillHeimlichkeit, &config)
+                    set_value(stealth.value, FieldName::SkillHeimlichkeit, sheet_config)
             }
 
             if let Some(streetwise) = skills.streetwise {
-                skill_streetwise_value_option =
-                    set_value(streetwise.value, FieldName::SkillSzenekenntnis, &config)
+                skill_streetwise_value_option = set_value(
+                    streetwise.value,
+                    FieldName::SkillSzenekenntnis,
+                    sheet_config,
+                )
             }
 
             if let Some(subterfuge) = skills.subterfuge {
                 skill_subterfuge_value_option =
-                    set_value(subterfuge.value, FieldName::SkillAusfluechte, &config)
+                    set_value(subterfuge.value, FieldName::SkillAusfluechte, sheet_config)
             }
 
             if let Some(survival) = skills.survival {
                 skill_survival_value_option =
-                    set_value(survival.value, FieldName::SkillÜberleben, &config)
+                    set_value(survival.value, FieldName::SkillÜberleben, sheet_config)
             }
         }
 
@@ -744,33 +770,33 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
             power_in_clan_disciplines_option = set_disciplines(
                 powers.in_clan_disciplines,
                 FieldName::InClanDisziplinen,
-                &config,
+                sheet_config,
             );
 
             power_out_of_clan_disciplines_option = set_disciplines(
                 powers.out_of_clan_disciplines,
                 FieldName::OutOfClanDisziplinen,
-                &config,
+                sheet_config,
             );
 
             power_in_clan_elder_powers_option = set_value_vec(
                 powers.in_clan_elder_powers,
                 FieldName::InClanAhnenkräfte,
-                &config,
+                sheet_config,
                 None,
             );
 
             power_out_of_clan_elder_powers_option = set_value_vec(
                 powers.out_of_clan_elder_powers,
                 FieldName::OutOfClanAhnenkräfte,
-                &config,
+                sheet_config,
                 None,
             );
 
             power_techniques_option = set_value_vec(
                 powers.techniques,
                 FieldName::Techniken,
-                &config,
+                sheet_config,
                 Some("".to_string()),
             );
         }
@@ -780,13 +806,16 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
 
         if let Some(morality) = self.morality {
             morality_name_option =
-                set_value(morality.name, FieldName::MoralvorstellungName, &config);
-            morality_value_option =
-                set_value(morality.value, FieldName::MoralvorstellungWert, &config);
+                set_value(morality.name, FieldName::MoralvorstellungName, sheet_config);
+            morality_value_option = set_value(
+                morality.value,
+                FieldName::MoralvorstellungWert,
+                sheet_config,
+            );
         }
 
         let faction_name_option: Option<ValueRange> =
-            set_value(self.faction, FieldName::FraktionName, &config);
+            set_value(self.faction, FieldName::FraktionName, sheet_config);
 
         let mut merits_and_flaws_option: Option<ValueRange> = None;
 
@@ -795,12 +824,12 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
                 self.merits,
                 self.flaws,
                 FieldName::MeritsFlawsName,
-                &config,
+                sheet_config,
             )
         }
 
         let backgrounds_option: Option<ValueRange> =
-            set_backgrounds(self.backgrounds, FieldName::Backgrounds, &config);
+            set_backgrounds(self.backgrounds, FieldName::Backgrounds, sheet_config);
 
         let mut experience_start_value_option: Option<ValueRange> = None;
 
@@ -808,12 +837,18 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
             experience_start_value_option = set_value(
                 Some(experience_information.start_value),
                 FieldName::ErfahrungspunkteStartpunkte,
-                &config,
+                sheet_config,
             )
         }
 
         let rituals_option: Option<ValueRange> =
-            set_rituals(self.rituals, FieldName::Rituale, &config);
+            set_rituals(self.rituals, FieldName::Rituale, sheet_config);
+        let attack_pool_values = set_attack_pools(
+            self.battle_information.and_then(|x| x.offense),
+            sheet_config,
+        );
+        let items_option: Option<ValueRange> =
+            set_items(self.items, FieldName::Items, sheet_config);
 
         // Checking if data was present, if not then we won't include that field in the payload for the google spreadsheet
         if let Some(character_name) = character_name_option {
@@ -1060,23 +1095,16 @@ impl Into<Vec<ValueRange>> for PlayerCharacterUpdateInput {
             result.push(ritual_values)
         }
 
+        result.extend(attack_pool_values);
+
+        if let Some(item_values) = items_option {
+            result.push(item_values)
+        }
+
         result
     }
 }
 
-/// Takes care of retrieving the value from the passed in result range
-fn extract_value_from_vec(
-    value_range: &[ValueRange],
-    field_name: FieldName,
-    sheet_config: &ConfigClient,
-) -> Option<Vec<Vec<String>>> {
-    value_range
-        .get(sheet_config.get_field_config(field_name).position as usize)
-        .unwrap()
-        .clone()
-        .values
-}
-
 /// creates a standardized value range
 fn create_value_range(
     values: Option<Vec<Vec<String>>>,
@@ -1090,18 +1118,6 @@ fn create_value_range(
     }
 }
 
-/// reads a single cell
-fn get_value<T>(value_range: &[ValueRange], field_name: FieldName, sheet_config: &ConfigClient) -> T
-where
-    T: FromStr + Default,
-    <T as FromStr>::Err: std::fmt::Debug,
-{
-    let binding = extract_value_from_vec(value_range, field_name, sheet_config)
-        .unwrap_or_else(|| vec![vec!["-".to_string()]]);
-    let field = binding.get(0).unwrap().get(0).unwrap().clone();
-    field.parse::<T>().unwrap_or_default()
-}
-
 /// sets a value in a single cell
 fn set_value<T>(
     value: Option<T>,
@@ -1129,30 +1145,6 @@ where
     }
 }
 
-/// extracts a vec
-fn get_value_vec(
-    value_range: &[ValueRange],
-    field_name: FieldName,
-    sheet_config: &ConfigClient,
-) -> Vec<String> {
-    let data = extract_value_from_vec(value_range, field_name, sheet_config);
-    let max_data_size: usize = match data {
-        Some(ref x) => x.len(),
-        None => 1,
-    };
-
-    data.unwrap_or_else(|| vec![vec!["-".to_string()]])
-        .get(0..max_data_size)
-        .unwrap()
-        .concat()
-        .iter()
-        .filter_map(|x| match x != "-" {
-            true => Some(x.clone()),
-            false => None,
-        })
-        .collect()
-}
-
 fn set_value_vec<T>(
     value: Option<Vec<T>>,
     field_name: FieldName,
@@ -1183,24 +1175,6 @@ where
     }
 }
 
-/// reads a skill specialization
-fn get_skill_specialization(
-    value_range: &[ValueRange],
-    field_name: FieldName,
-    sheet_config: &ConfigClient,
-) -> Option<Vec<String>> {
-    Some(
-        get_value::<String>(value_range, field_name, sheet_config)
-            .split(',')
-            .map(|x| x.trim().to_string())
-            .filter_map(|x| match x != "-" {
-                true => Some(x),
-                false => None,
-            })
-            .collect(),
-    )
-}
-
 // sets a skill specialization
 fn set_skill_specialization(
     value: Option<Vec<String>>,
@@ -1224,27 +1198,6 @@ fn set_skill_specialization(
     }
 }
 
-/// reads the list of disciplines
-fn get_disciplines(
-    value_range: &[ValueRange],
-    field_name: FieldName,
-    sheet_config: &ConfigClient,
-) -> Vec<Discipline> {
-    extract_value_from_vec(value_range, field_name, sheet_config)
-        .unwrap()
-        .get(0..6)
-        .unwrap()
-        .iter()
-        .filter(|x| x.get(0).unwrap() != "-")
-        .map(|x| -> Discipline {
-            Discipline {
-                name: x.get(0).unwrap().clone(),
-                value: x.get(7).unwrap().parse().unwrap_or(0),
-            }
-        })
-        .collect::<Vec<Discipline>>()
-}
-
 /// sets the list of disciplines
 fn set_disciplines(
     value: Option<Vec<DisciplineUpdateInput>>,
@@ -1282,88 +1235,6 @@ fn set_disciplines(
     }
 }
 
-/// reads the merits of the combined list
-fn extract_merits(input: &[Vec<String>]) -> Vec<Merit> {
-    let re = Regex::new(r"^(V(?P<merit_type>.*):|V )(?P<merit_name>.+)$").unwrap();
-
-    input
-        .get(0..7)
-        .unwrap()
-        .iter()
-        .filter(|x| x.get(0).unwrap() != "-")
-        .filter(|x| !x.get(0).unwrap().starts_with('N'))
-        .map(|x| -> Merit {
-            let captured: Option<Captures> = re.captures(x.get(0).unwrap());
-
-            let name_normalized = match captured.as_ref().and_then(|cap| {
-                cap.name("merit_name")
-                    .map(|merit_name| merit_name.as_str().trim().to_string())
-            }) {
-                Some(value) => value,
-                None => x.get(0).unwrap().clone(),
-            };
-
-            let merit_type = match captured.and_then(|cap| {
-                cap.name("merit_type")
-                    .map(|merit_type| merit_type.as_str().trim().to_string())
-            }) {
-                Some(value) => value,
-                None => "General".to_string(),
-            };
-
-            Merit {
-                name: name_normalized,
-                value: match x.get(7).unwrap().parse() {
-                    Ok(val) => val,
-                    Err(..) => 0,
-                },
-                merit_type,
-            }
-        })
-        .collect()
-}
-
-/// reads the flaws of the combined list
-fn extract_flaws(input: &[Vec<String>]) -> Vec<Flaw> {
-    let re = Regex::new(r"^(N(?P<flaw_type>.*):|N )(?P<flaw_name>.+)$").unwrap();
-
-    input
-        .get(0..7)
-        .unwrap()
-        .iter()
-        .filter(|x| x.get(0).unwrap() != "-")
-        .filter(|x| x.get(0).unwrap().starts_with('N'))
-        .map(|x| -> Flaw {
-            let captured: Option<Captures> = re.captures(x.get(0).unwrap());
-
-            let name_normalized = match captured.as_ref().and_then(|cap| {
-                cap.name("flaw_name")
-                    .map(|flaw_name| flaw_name.as_str().trim().to_string())
-            }) {
-                Some(value) => value,
-                None => x.get(0).unwrap().clone(),
-            };
-
-            let flaw_type = match captured.and_then(|cap| {
-                cap.name("flaw_type")
-                    .map(|flaw_type| flaw_type.as_str().trim().to_string())
-            }) {
-                Some(value) => value,
-                None => "General".to_string(),
-            };
-
-            Flaw {
-                name: name_normalized,
-                value: match x.get(7).unwrap().parse() {
-                    Ok(val) => val,
-                    Err(..) => 0,
-                },
-                flaw_type,
-            }
-        })
-        .collect()
-}
-
 fn combine_merits_and_flaws(
     merits: Option<Vec<MeritUpdateInput>>,
     flaws: Option<Vec<FlawUpdateInput>>,
@@ -1407,7 +1278,7 @@ fn combine_merits_and_flaws(
     }
 
     // Clearing (possibly) remaining entries in the discipline list in the sheet
-    for _ in data_to_write.iter().len()..=config_entry.range_length.unwrap() as usize {
+    for _ in data_to_write.iter().len()..config_entry.range_length.unwrap() as usize {
         let row_vec: Vec<String> = vec!["-".to_string()];
         data_to_write.push(row_vec);
     }
@@ -1419,28 +1290,6 @@ fn combine_merits_and_flaws(
     ))
 }
 
-/// reads the list of backgrounds
-fn get_backgrounds(
-    value_range: &[ValueRange],
-    field_name: FieldName,
-    sheet_config: &ConfigClient,
-) -> Vec<Background> {
-    extract_value_from_vec(value_range, field_name, sheet_config)
-        .unwrap()
-        .get(0..9)
-        .unwrap()
-        .iter()
-        .filter(|x| x.get(0).unwrap().trim() != "")
-        .map(|x| -> Background {
-            Background {
-                name: x.get(0).unwrap().clone(),
-                value: x.get(7).unwrap().parse().unwrap_or(0),
-                description: x.get(9).unwrap_or(&"".to_string()).clone(),
-            }
-        })
-        .collect::<Vec<Background>>()
-}
-
 /// converts the list of backgrounds into a value range
 fn set_backgrounds(
     value: Option<Vec<BackgroundUpdateInput>>,
@@ -1467,7 +1316,7 @@ fn set_backgrounds(
             }
 
             // Clearing (possibly) remaining entries in the discipline list in the sheet
-            for n in data_to_write.iter().len()..=config_entry.range_length.unwrap() as usize {
+            for n in data_to_write.iter().len()..config_entry.range_length.unwrap() as usize {
                 let mut row_vec: Vec<String> = vec!["".to_string(); 10];
                 row_vec[7] = "-".to_string();
                 data_to_write.insert(n, row_vec);
@@ -1483,181 +1332,101 @@ fn set_backgrounds(
     }
 }
 
-/// reads a health track information
-fn get_health_track(
-    value_range: &[ValueRange],
-    field_name: FieldName,
+/// sets the selections and descriptions of the attack pools, one column at a time
+///
+/// The columns in between hold the formulas computing the values and the pool, they are never written.
+fn set_attack_pools(
+    value: Option<Vec<BattleOffenseUpdateInput>>,
     sheet_config: &ConfigClient,
-) -> HealthTrack {
-    let binding = extract_value_from_vec(value_range, field_name, sheet_config).unwrap();
-    let track = binding.get(0).unwrap().get(0..8).unwrap();
-    let base_value = track.get(5).unwrap().parse::<u8>().unwrap();
-    let with_boni = track.get(7).unwrap().parse::<u8>().unwrap();
-
-    let mut lost: u8 = 0;
-
-    for n in 0..5 {
-        match track.get(n).unwrap().trim() {
-            "x" => lost += 1,
-            _ => break,
-        }
-    }
-
-    let remaining: u8 = match lost > with_boni {
-        true => 0,
-        false => with_boni - lost,
+) -> Vec<ValueRange> {
+    // attack pools which are not part of the update stay untouched
+    let val = match value {
+        Some(val) => val,
+        None => return Vec::new(),
     };
 
-    HealthTrack {
-        base_value,
-        with_boni,
-        remaining,
+    let mut skills: Vec<Vec<String>> = Vec::new();
+    let mut attributes: Vec<Vec<String>> = Vec::new();
+    let mut wildcards: Vec<Vec<String>> = Vec::new();
+    let mut descriptions: Vec<Vec<String>> = Vec::new();
+
+    for entry in val {
+        skills.push(vec![entry.skill.name]);
+        attributes.push(vec![entry.attribute.name]);
+        wildcards.push(vec![entry
+            .wildcard
+            .map(|x| x.name)
+            .unwrap_or_else(|| "-".to_string())]);
+        descriptions.push(vec![entry.description]);
     }
-}
 
-/// reads the non-physical defense pools (social/mental)
-fn get_non_physical_defense_pool(
-    value_range: &[ValueRange],
-    field_name: FieldName,
-    sheet_config: &ConfigClient,
-) -> HashMap<u8, u8> {
-    let mut result: HashMap<u8, u8> = HashMap::new();
-    let values = extract_value_from_vec(value_range, field_name, sheet_config)
-        .unwrap()
-        .get(0)
-        .unwrap()
-        .clone();
-    let first_value = values[0].parse::<u8>().unwrap();
-    result.insert(0, first_value);
-    for n in 2_u8..9_u8 {
-        result.insert(n - 1, values[n as usize].parse::<u8>().unwrap());
+    // Clearing (possibly) remaining entries in the attack pool list in the sheet
+    let range_length = sheet_config
+        .get_field_config(FieldName::AngriffsPoolsFertigkeit)
+        .range_length
+        .unwrap() as usize;
+
+    for _ in skills.len()..range_length {
+        skills.push(vec!["-".to_string()]);
+        attributes.push(vec!["-".to_string()]);
+        wildcards.push(vec!["-".to_string()]);
+        descriptions.push(vec!["".to_string()]);
     }
 
-    result
+    vec![
+        create_value_range(
+            Some(skills),
+            FieldName::AngriffsPoolsFertigkeit,
+            sheet_config,
+        ),
+        create_value_range(
+            Some(attributes),
+            FieldName::AngriffsPoolsAttribut,
+            sheet_config,
+        ),
+        create_value_range(Some(wildcards), FieldName::AngriffsPoolsJoker, sheet_config),
+        create_value_range(
+            Some(descriptions),
+            FieldName::AngriffsPoolsBeschreibung,
+            sheet_config,
+        ),
+    ]
 }
 
-/// reads the different attack pools
-fn get_attack_pools(
-    value_range: &[ValueRange],
+/// sets the items list, the traits and their descriptions sit in merged cells of the items block
+fn set_items(
+    value: Option<Vec<ItemUpdateInput>>,
     field_name: FieldName,
     sheet_config: &ConfigClient,
-) -> Vec<BattleOffenseInformation> {
-    let mut result: Vec<BattleOffenseInformation> = Vec::new();
-    let binding = extract_value_from_vec(value_range, field_name, sheet_config).unwrap();
-    let values = binding.get(0..24).unwrap();
-
-    for entry in values.iter() {
-        let offense_pool_data = entry.clone();
-        let skill_name: String = offense_pool_data.get(0).unwrap_or(&"-".to_string()).clone();
-        let skill_value: u8 = offense_pool_data.get(7).unwrap().parse::<u8>().unwrap_or(0);
-        let attribute_name: String = offense_pool_data
-            .get(10)
-            .unwrap_or(&"-".to_string())
-            .clone();
-        let attribute_value: u8 = offense_pool_data
-            .get(14)
-            .unwrap()
-            .parse::<u8>()
-            .unwrap_or(0);
-        let wildcard_name: String = offense_pool_data
-            .get(17)
-            .unwrap_or(&"-".to_string())
-            .clone();
-        let wildcard_value: u8 = offense_pool_data
-            .get(21)
-            .unwrap()
-            .parse::<u8>()
-            .unwrap_or(0);
-        let pool: u8 = offense_pool_data
-            .get(24)
-            .unwrap()
-            .parse::<u8>()
-            .unwrap_or(0);
-        let description: String = offense_pool_data.get(26).unwrap_or(&"".to_string()).clone();
-
-        if skill_name.ne("-") && attribute_name.ne("-") {
-            let offense_pool_entry = BattleOffenseInformation {
-                skill: NameValue {
-                    name: skill_name,
-                    value: skill_value,
-                },
-                attribute: NameValue {
-                    name: attribute_name,
-                    value: attribute_value,
-                },
-                wildcard: NameValue {
-                    name: wildcard_name,
-                    value: wildcard_value,
-                },
-                pool,
-                description,
-            };
+) -> Option<ValueRange> {
+    let mut data_to_write: Vec<Vec<String>> = Vec::new();
+    let config_entry = sheet_config.get_field_config(field_name.clone());
 
-            result.push(offense_pool_entry);
-        }
+    // items which are not part of the update stay untouched
+    let val = value?;
+
+    for entry in val {
+        let mut row_vec: Vec<String> = vec!["".to_string(); 26];
+        row_vec[0] = entry.name;
+        row_vec[5] = entry.trait_1;
+        row_vec[9] = entry.trait_1_description;
+        row_vec[13] = entry.trait_2;
+        row_vec[17] = entry.trait_2_description;
+        row_vec[21] = entry.additional_trait;
+        row_vec[25] = entry.additional_trait_description;
+        data_to_write.push(row_vec);
     }
 
-    result
-}
-
-/// reads the rituals section
-fn get_rituals(
-    value_range: &[ValueRange],
-    field_name: FieldName,
-    sheet_config: &ConfigClient,
-) -> Vec<Ritual> {
-    let mut result: Vec<Ritual> = Vec::new();
-    let binding = extract_value_from_vec(value_range, field_name, sheet_config).unwrap();
-    let values = binding.get(0..15).unwrap();
-
-    for entry in values.iter() {
-        let ritual_data = entry.clone();
-        let ritual_name: String = ritual_data.get(0).unwrap_or(&"-".to_string()).clone();
-        let level: u8 = ritual_data.get(7).unwrap().parse::<u8>().unwrap_or(0);
-        let description: String = ritual_data.get(9).unwrap_or(&"-".to_string()).clone();
-
-        if ritual_name.ne("-") && level.ne(&0) {
-            let re = Regex::new(r"^(?P<ritual_type>\w{1})\d{1}(?P<ritual_name>.+)$").unwrap();
-
-            let captured: Option<Captures> = re.captures(ritual_name.as_str());
-
-            let ritual_type = match captured.as_ref().and_then(|cap| {
-                cap.name("ritual_type").map(|ritual_type| {
-                    *ritual_type
-                        .as_str()
-                        .trim()
-                        .chars()
-                        .collect::<Vec<char>>()
-                        .first()
-                        .unwrap()
-                })
-            }) {
-                Some('A') => "Abyssal".to_string(),
-                Some('N') => "Necromancy".to_string(),
-                Some('T') => "Thaumaturgy".to_string(),
-                _ => "Unbekannt".to_string(),
-            };
-
-            let name: String = captured
-                .and_then(|cap| {
-                    cap.name("ritual_name")
-                        .map(|ritual_name| Some(ritual_name.as_str().trim().to_string()))
-                        .unwrap()
-                })
-                .unwrap();
-
-            let ritual_entry = Ritual {
-                name,
-                level,
-                description,
-                ritual_type,
-            };
-
-            result.push(ritual_entry);
-        }
+    // Clearing (possibly) remaining entries in the item list in the sheet
+    for _ in data_to_write.iter().len()..config_entry.range_length.unwrap() as usize {
+        data_to_write.push(vec!["".to_string(); 26]);
     }
 
-    result
+    Some(create_value_range(
+        Some(data_to_write),
+        field_name,
+        sheet_config,
+    ))
 }
 
 /// sets the rituals list
@@ -1669,25 +1438,26 @@ fn set_rituals(
     let mut data_to_write: Vec<Vec<String>> = Vec::new();
     let config_entry = sheet_config.get_field_config(field_name.clone());
 
-    if let Some(val) = value {
-        for entry in val {
-            let mut row_vec: Vec<String> = Vec::new();
+    // rituals which are not part of the update stay untouched
+    let val = value?;
 
-            let ritual_type = match entry.ritual_type.as_ref() {
-                "Abyssal" => 'A',
-                "Necromancy" => 'N',
-                "Thaumaturgy" => 'T',
-                _ => 'U',
-            };
+    for entry in val {
+        let mut row_vec: Vec<String> = Vec::new();
 
-            row_vec.push(format!("{}{} {}", ritual_type, entry.level, entry.name));
+        let ritual_type = match entry.ritual_type.as_ref() {
+            "Abyssal" => 'A',
+            "Necromancy" => 'N',
+            "Thaumaturgy" => 'T',
+            _ => 'U',
+        };
 
-            data_to_write.push(row_vec);
-        }
+        row_vec.push(format!("{}{} {}", ritual_type, entry.level, entry.name));
+
+        data_to_write.push(row_vec);
     }
 
     // Clearing (possibly) remaining entries in the discipline list in the sheet
-    for _ in data_to_write.iter().len()..=config_entry.range_length.unwrap() as usize {
+    for _ in data_to_write.iter().len()..config_entry.range_length.unwrap() as usize {
         let row_vec: Vec<String> = vec!["-".to_string()];
         data_to_write.push(row_vec);
     }
@@ -1699,46 +1469,184 @@ fn set_rituals(
     ))
 }
 
-/// reads the items section
-fn get_items(
-    value_range: &[ValueRange],
-    field_name: FieldName,
+/// rejects value ranges the scope may not write or which do not fit into their range
+///
+/// Every write to the sheet goes through this check, whether it comes from an update or another endpoint.
+pub fn check_value_ranges(
+    value_ranges: &[ValueRange],
     sheet_config: &ConfigClient,
-) -> Vec<Item> {
-    let mut result: Vec<Item> = Vec::new();
-    let binding = extract_value_from_vec(value_range, field_name, sheet_config).unwrap_or_default();
+    scope: ApiKeyScope,
+) -> Result<(), GatewayError> {
+    let mut protected: Vec<String> = Vec::new();
+
+    for value_range in value_ranges {
+        let range = value_range
+            .range
+            .as_deref()
+            .unwrap_or_default()
+            .parse::<SheetRange>()
+            .map_err(GatewayError::Internal)?;
+
+        check_size(value_range, &range, sheet_config)?;
+
+        // ranges overlap without being equal, e.g. a column within a block of the sheet
+        for entry in sheet_config.settings.sheet_field.iter().filter(|x| {
+            x.range
+                .parse::<SheetRange>()
+                .is_ok_and(|entry_range| entry_range.intersects(&range))
+        }) {
+            let allowed = entry.is_writable()
+                && (!entry.is_storyteller_only() || scope == ApiKeyScope::Storyteller);
+
+            if !allowed {
+                protected.push(format!("{} ({})", entry.name, entry.range));
+            }
+        }
+    }
 
-    if binding.is_empty() {
-        return Vec::new();
+    match protected.is_empty() {
+        true => Ok(()),
+        false => Err(GatewayError::ProtectedRange(protected)),
     }
+}
 
-    let number_of_items = binding.len();
-    let values = binding.get(0..number_of_items).unwrap();
-
-    for entry in values.iter() {
-        let item_data = entry.clone();
-        let name = item_data.get(0).unwrap_or(&"".to_string()).clone();
-        let trait_1 = item_data.get(5).unwrap_or(&"".to_string()).clone();
-        let trait_1_description = item_data.get(9).unwrap_or(&"".to_string()).clone();
-        let trait_2 = item_data.get(13).unwrap_or(&"".to_string()).clone();
-        let trait_2_description = item_data.get(17).unwrap_or(&"".to_string()).clone();
-        let additional_trait = item_data.get(21).unwrap_or(&"".to_string()).clone();
-        let additional_trait_description = item_data.get(25).unwrap_or(&"".to_string()).clone();
-
-        if name.ne(&"".to_string()) {
-            let result_entry = Item {
-                name,
-                trait_1,
-                trait_1_description,
-                trait_2,
-                trait_2_description,
-                additional_trait,
-                additional_trait_description,
-            };
+/// rejects a list with more entries than the rows of its range
+fn check_length<T>(
+    list: Option<&Vec<T>>,
+    name: &str,
+    range_length: Option<u8>,
+) -> Result<(), GatewayError> {
+    let length = list.map(Vec::len).unwrap_or_default();
+    let range_length = range_length.unwrap_or_default() as usize;
+
+    match length <= range_length {
+        true => Ok(()),
+        false => Err(GatewayError::BadRequest(format!(
+            "the sheet holds at most {} {}, the update has {}",
+            range_length, name, length
+        ))),
+    }
+}
+
+/// rejects values which do not fit into their range, they would overwrite the cells below or beside it
+fn check_size(
+    value_range: &ValueRange,
+    range: &SheetRange,
+    sheet_config: &ConfigClient,
+) -> Result<(), GatewayError> {
+    let values = value_range.values.as_deref().unwrap_or_default();
+    let columns = values.iter().map(Vec::len).max().unwrap_or_default();
+
+    if values.len() <= range.rows() && columns <= range.columns() {
+        return Ok(());
+    }
+
+    let field = sheet_config
+        .settings
+        .sheet_field
+        .iter()
+        .find(|x| Some(&x.range) == value_range.range.as_ref())
+        .map(|x| x.name.to_string())
+        .unwrap_or_else(|| range.to_string());
+
+    Err(GatewayError::BadRequest(format!(
+        "{} takes at most {} rows of {} cells, the update has {} rows of up to {} cells",
+        field,
+        range.rows(),
+        range.columns(),
+        values.len(),
+        columns
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::sheet_layouts::SheetLayouts;
 
-            result.push(result_entry);
+    fn layout() -> ConfigClient {
+        SheetLayouts::load("FieldConfig.toml", "layouts")
+            .unwrap()
+            .default_layout()
+            .as_ref()
+            .clone()
+    }
+
+    fn value_range(range: &str, rows: usize, columns: usize) -> ValueRange {
+        ValueRange {
+            major_dimension: Some("ROWS".to_string()),
+            range: Some(range.to_string()),
+            values: Some(vec![vec![String::new(); columns]; rows]),
         }
     }
 
-    result
+    #[test]
+    fn lists_up_to_the_range_length_are_accepted() {
+        assert!(check_length(Some(&vec![0; 10]), "items", Some(10)).is_ok());
+        assert!(check_length::<u8>(None, "items", Some(10)).is_ok());
+    }
+
+    #[test]
+    fn more_items_than_the_sheet_holds_are_rejected() {
+        let layout = layout();
+        let range_length = layout.get_field_config(FieldName::Items).range_length;
+
+        let result = check_length(Some(&vec![0; 11]), "items", range_length);
+
+        assert!(matches!(result, Err(GatewayError::BadRequest(_))));
+    }
+
+    #[test]
+    fn more_attack_pools_than_the_sheet_holds_are_rejected() {
+        let layout = layout();
+        let range_length = layout
+            .get_field_config(FieldName::AngriffsPoolsFertigkeit)
+            .range_length;
+
+        let result = check_length(Some(&vec![0; 25]), "attack pools", range_length);
+
+        assert!(matches!(result, Err(GatewayError::BadRequest(_))));
+    }
+
+    #[test]
+    fn values_fitting_their_range_are_accepted() {
+        let layout = layout();
+        let range = layout.get_field_config(FieldName::Rituale).range;
+
+        let result = check_size(
+            &value_range(&range, 15, 10),
+            &range.parse().unwrap(),
+            &layout,
+        );
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn lists_longer_than_their_range_are_rejected() {
+        let layout = layout();
+        let range = layout.get_field_config(FieldName::Rituale).range;
+
+        let result = check_size(
+            &value_range(&range, 16, 10),
+            &range.parse().unwrap(),
+            &layout,
+        );
+
+        assert!(matches!(result, Err(GatewayError::BadRequest(_))));
+    }
+
+    #[test]
+    fn rows_wider_than_their_range_are_rejected() {
+        let layout = layout();
+        let range = layout.get_field_config(FieldName::Rituale).range;
+
+        let result = check_size(
+            &value_range(&range, 1, 11),
+            &range.parse().unwrap(),
+            &layout,
+        );
+
+        assert!(matches!(result, Err(GatewayError::BadRequest(_))));
+    }
 }
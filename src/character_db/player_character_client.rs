@@ -1,118 +1,411 @@
-extern crate google_sheets4 as sheets4;
-extern crate yup_oauth2 as oauth2;
-
-use crate::config::config_client::ConfigClient;
+use crate::{
+    api_key_scope::ApiKeyScope,
+    config::{config_client::ConfigClient, field_name::FieldName, sheet_layouts::SheetLayouts},
+    gateway_error::GatewayError,
+};
 
-use super::player_character::{PlayerCharacter, PlayerCharacterUpdateInput};
-use hyper::{client::HttpConnector, StatusCode};
-use hyper_rustls::HttpsConnector;
-use sheets4::{
-    api::{BatchUpdateValuesRequest, ValueRange},
-    Sheets,
+use super::{
+    character_source::CharacterSource,
+    health_tracks::HealthTracks,
+    player_character::{check_value_ranges, PlayerCharacter, PlayerCharacterUpdateInput},
+    range_change::{range_changes, RangeChange},
+    sheet_parser::SheetParser,
 };
+use google_sheets4::api::ValueRange;
+use std::sync::Arc;
 
 pub struct PlayerCharacterClient {
-    hub: Sheets<HttpsConnector<HttpConnector>>,
-    sheet_config: ConfigClient,
+    source: Arc<dyn CharacterSource>,
+    layouts: Arc<SheetLayouts>,
 }
 
 impl PlayerCharacterClient {
-    pub async fn new(
-        service_account_information: String,
-    ) -> Result<PlayerCharacterClient, StatusCode> {
-        let secret = if let Ok(credentials) =
-            oauth2::parse_service_account_key(&service_account_information)
-        {
-            tracing::debug!("parsed credentials successfully from env variable");
-            credentials
-        } else {
-            tracing::debug!(
-                "parsed credentials unsuccessfully from env variable, trying credentials.json now"
+    pub fn new(
+        source: Arc<dyn CharacterSource>,
+        layouts: Arc<SheetLayouts>,
+    ) -> PlayerCharacterClient {
+        PlayerCharacterClient { source, layouts }
+    }
+
+    pub async fn parse_data(&self, sheet_id: String) -> Result<PlayerCharacter, GatewayError> {
+        // the ranges differ between the layouts, so only the version cell is read up front
+        let layout = self.load_layout(&sheet_id).await?;
+        let data = self.load_data(&sheet_id, &layout).await?;
+        let player_character = PlayerCharacter::parse(&data, &layout)?;
+
+        if !player_character.warnings.is_empty() {
+            tracing::warn!(
+                "{} cells of the sheet could not be read",
+                player_character.warnings.len()
             );
-            if let Ok(credentials) = oauth2::read_service_account_key("credentials.json").await {
-                tracing::debug!("parsed credentials successfully from credentials.json");
-                credentials
-            } else {
-                tracing::debug!("both parse attempts failed, giving up");
-                return Err(StatusCode::INTERNAL_SERVER_ERROR);
-            }
-        };
+        }
 
-        let authenticator = oauth2::ServiceAccountAuthenticator::builder(secret)
-            .build()
-            .await
-            .expect("failed to create authenticator");
-
-        let hub = Sheets::new(
-            hyper::Client::builder().build(
-                hyper_rustls::HttpsConnectorBuilder::new()
-                    .with_native_roots()
-                    .https_or_http()
-                    .enable_http1()
-                    .enable_http2()
-                    .build(),
-            ),
-            authenticator,
-        );
+        Ok(player_character)
+    }
 
-        let sheet_config = ConfigClient::new();
-        Ok(PlayerCharacterClient { hub, sheet_config })
+    async fn load_data(
+        &self,
+        sheet_key: &str,
+        sheet_config: &ConfigClient,
+    ) -> Result<Vec<ValueRange>, GatewayError> {
+        let ranges: Vec<String> = sheet_config
+            .get_field_config_sorted()
+            .into_iter()
+            .filter(|entry| !entry.exclude_on_read.unwrap_or(false))
+            .map(|entry| entry.range)
+            .collect();
+
+        self.source.load_ranges(sheet_key, &ranges).await
     }
 
-    pub async fn parse_data(&mut self, sheet_id: String) -> Result<PlayerCharacter, StatusCode> {
-        match self.load_data(sheet_id).await {
-            Ok(data) => Ok(PlayerCharacter::from(data)),
-            Err(err) => Err(err),
+    /// reads the version cell of the sheet and returns the matching layout
+    async fn load_layout(&self, sheet_key: &str) -> Result<Arc<ConfigClient>, GatewayError> {
+        let default_layout = self.layouts.default_layout();
+        let version_range = default_layout
+            .get_field_config(FieldName::VersionSheet)
+            .range;
+
+        let data = self.source.load_ranges(sheet_key, &[version_range]).await?;
+        let version = read_version(&data, &default_layout)?;
+        let layout = self.layouts.for_version(&version);
+
+        if !Arc::ptr_eq(&layout, &default_layout) {
+            tracing::debug!("sheet {} uses the layout of version {}", sheet_key, version);
         }
+
+        Ok(layout)
     }
 
-    async fn load_data(&self, sheet_key: String) -> Result<Vec<ValueRange>, StatusCode> {
-        let mut result = self.hub.spreadsheets().values_batch_get(sheet_key.as_str());
+    /// writes only the cells whose value changes and returns their number
+    ///
+    /// Cells keeping their value are not sent, so their formatting and concurrent manual edits of
+    /// other cells in the same range survive.
+    pub async fn write_data(
+        &self,
+        sheet_key: String,
+        character: PlayerCharacterUpdateInput,
+        scope: ApiKeyScope,
+    ) -> Result<i32, GatewayError> {
+        let range_changes = self
+            .preview_data(sheet_key.clone(), character, scope)
+            .await?;
 
-        for entry in self.sheet_config.get_field_config_sorted() {
-            if entry.exclude_on_read.unwrap_or(false) {
-                continue;
-            }
+        self.write_changes(&sheet_key, range_changes).await
+    }
 
-            result = result.add_ranges(&entry.range);
-        }
+    /// marks the lost boxes of the health tracks and returns the number of written cells
+    pub async fn write_health(
+        &self,
+        sheet_key: &str,
+        health: &HealthTracks,
+        scope: ApiKeyScope,
+    ) -> Result<i32, GatewayError> {
+        let layout = self.load_layout(sheet_key).await?;
+        let value_ranges = health.value_ranges(&layout);
+        check_value_ranges(&value_ranges, &layout, scope)?;
 
-        let response = result.doit().await;
+        let range_changes = self.plan_changes(sheet_key, value_ranges).await?;
 
-        match response {
-            Ok(my_result) => Ok(my_result.1.value_ranges.unwrap()),
-            Err(..) => Err(StatusCode::NOT_FOUND),
-        }
+        self.write_changes(sheet_key, range_changes).await
     }
 
-    pub async fn write_data(
+    /// the ranges the update covers with their current and new values, nothing is written
+    pub async fn preview_data(
         &self,
         sheet_key: String,
         character: PlayerCharacterUpdateInput,
-    ) -> Result<i32, StatusCode> {
-        let update_request = BatchUpdateValuesRequest {
-            data: Some(character.into()),
-            include_values_in_response: None,
-            response_date_time_render_option: None,
-            response_value_render_option: None,
-            value_input_option: Some("USER_ENTERED".to_string()),
-        };
-
-        tracing::trace!("update_request: {:?}", update_request);
-
-        let response = self
-            .hub
-            .spreadsheets()
-            .values_batch_update(update_request, &sheet_key)
-            .doit()
+        scope: ApiKeyScope,
+    ) -> Result<Vec<RangeChange>, GatewayError> {
+        let layout = self.load_layout(&sheet_key).await?;
+        let value_ranges = character.into_value_ranges(&layout, scope)?;
+
+        self.plan_changes(&sheet_key, value_ranges).await
+    }
+
+    /// rejects an update the scope may not write, without reading or writing any other cell
+    pub async fn check_data(
+        &self,
+        sheet_key: &str,
+        character: PlayerCharacterUpdateInput,
+        scope: ApiKeyScope,
+    ) -> Result<(), GatewayError> {
+        let layout = self.load_layout(sheet_key).await?;
+
+        character.into_value_ranges(&layout, scope).map(|_| ())
+    }
+
+    /// pairs the value ranges with the current values of the sheet
+    async fn plan_changes(
+        &self,
+        sheet_key: &str,
+        value_ranges: Vec<ValueRange>,
+    ) -> Result<Vec<RangeChange>, GatewayError> {
+        if value_ranges.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ranges: Vec<String> = value_ranges
+            .iter()
+            .filter_map(|x| x.range.clone())
+            .collect();
+        let current = self.source.load_ranges(sheet_key, &ranges).await?;
+
+        Ok(range_changes(&value_ranges, &current))
+    }
+
+    /// writes only the changed cells of the ranges
+    async fn write_changes(
+        &self,
+        sheet_key: &str,
+        range_changes: Vec<RangeChange>,
+    ) -> Result<i32, GatewayError> {
+        let cell_updates: Vec<ValueRange> = range_changes
+            .iter()
+            .flat_map(|x| x.cell_updates())
+            .collect();
+
+        if cell_updates.is_empty() {
+            tracing::debug!("the update does not change sheet {}", sheet_key);
+            return Ok(0);
+        }
+
+        self.source.update_ranges(sheet_key, cell_updates).await
+    }
+}
+
+/// reads the version cell, which is at the same place in every layout
+fn read_version(
+    data: &[ValueRange],
+    default_layout: &ConfigClient,
+) -> Result<String, GatewayError> {
+    SheetParser::new(data, default_layout).get_value::<String>(FieldName::VersionSheet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character_db::{
+        health_box::DamageType, in_memory_source::InMemorySource, ritual::RitualUpdateInput,
+        skill::SkillUpdateInput, skills::SkillsUpdateInput,
+    };
+
+    const SHEET: &str = "example-sheet";
+
+    fn client() -> PlayerCharacterClient {
+        let source = InMemorySource::from_fixture_file("fixtures/example_character.json").unwrap();
+        let layouts = SheetLayouts::load("FieldConfig.toml", "layouts").unwrap();
+
+        PlayerCharacterClient::new(Arc::new(source), Arc::new(layouts))
+    }
+
+    fn empty_update() -> PlayerCharacterUpdateInput {
+        serde_json::from_str("{}").unwrap()
+    }
+
+    #[tokio::test]
+    async fn parses_the_fixture() {
+        let player_character = client().parse_data(SHEET.to_string()).await.unwrap();
+
+        assert_eq!(player_character.character_name, "Johanna Weiss");
+        assert_eq!(player_character.player_name, "Erika Mustermann");
+        assert_eq!(player_character.clan, "Tremere");
+        assert_eq!(player_character.blood_pool, 13);
+        assert!(player_character.warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unknown_sheets_are_not_found() {
+        let result = client().parse_data("missing".to_string()).await;
+
+        assert!(matches!(result, Err(GatewayError::SheetNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn an_update_is_read_back() {
+        let client = client();
+        let mut update = empty_update();
+        update.character_name = Some("Johanna Schwarz".to_string());
+        update.skills = Some(SkillsUpdateInput {
+            occult: Some(SkillUpdateInput {
+                value: Some(4),
+                foci: None,
+            }),
+            ..serde_json::from_str("{}").unwrap()
+        });
+
+        let updated_cells = client
+            .write_data(SHEET.to_string(), update, ApiKeyScope::Storyteller)
+            .await
+            .unwrap();
+        let player_character = client.parse_data(SHEET.to_string()).await.unwrap();
+
+        assert_eq!(updated_cells, 2);
+        assert_eq!(player_character.character_name, "Johanna Schwarz");
+        assert_eq!(player_character.skills.occult.value, 4);
+        assert_eq!(player_character.clan, "Tremere");
+    }
+
+    #[tokio::test]
+    async fn sections_left_out_stay_untouched() {
+        let client = client();
+        let before = client.parse_data(SHEET.to_string()).await.unwrap();
+        let mut update = empty_update();
+        update.rituals = Some(vec![RitualUpdateInput {
+            name: "Blutschutz".to_string(),
+            level: 1,
+            ritual_type: "Thaumaturgy".to_string(),
+        }]);
+
+        client
+            .write_data(SHEET.to_string(), update, ApiKeyScope::Storyteller)
+            .await
+            .unwrap();
+        let after = client.parse_data(SHEET.to_string()).await.unwrap();
+
+        assert_eq!(after.rituals.len(), 1);
+        assert_eq!(after.rituals[0].name, "Blutschutz");
+        assert_eq!(after.merits.len(), before.merits.len());
+        assert_eq!(after.items.len(), before.items.len());
+    }
+
+    #[tokio::test]
+    async fn writing_the_current_values_changes_nothing() {
+        let client = client();
+        let mut update = empty_update();
+        update.character_name = Some("Johanna Weiss".to_string());
+
+        let updated_cells = client
+            .write_data(SHEET.to_string(), update, ApiKeyScope::Storyteller)
+            .await
+            .unwrap();
+
+        assert_eq!(updated_cells, 0);
+    }
+
+    #[tokio::test]
+    async fn storyteller_only_ranges_follow_the_configuration() {
+        let client = client();
+        let mut update = empty_update();
+        update.clan = Some("Brujah".to_string());
+
+        let player = client
+            .check_data(SHEET, update.clone(), ApiKeyScope::PlayerWrite)
+            .await;
+        let storyteller = client
+            .check_data(SHEET, update, ApiKeyScope::Storyteller)
             .await;
 
-        match response {
-            Ok(my_result) => Ok(my_result.1.total_updated_cells.unwrap()),
-            Err(err) => {
-                tracing::error!("{:?}", err);
-                Err(StatusCode::BAD_REQUEST)
+        assert!(matches!(player, Err(GatewayError::ProtectedRange(_))));
+        assert!(storyteller.is_ok());
+    }
+
+    #[tokio::test]
+    async fn a_storyteller_may_write_the_whole_character() {
+        let client = client();
+        let player_character = client.parse_data(SHEET.to_string()).await.unwrap();
+        let update: PlayerCharacterUpdateInput =
+            serde_json::from_value(serde_json::to_value(player_character).unwrap()).unwrap();
+
+        // the merit names lie within the merits block, overlapping ranges are fine unless protected
+        let result = client
+            .check_data(SHEET, update, ApiKeyScope::Storyteller)
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn ranges_overlapping_a_protected_range_are_rejected() {
+        let mut layout = SheetLayouts::load("FieldConfig.toml", "layouts")
+            .unwrap()
+            .default_layout()
+            .as_ref()
+            .clone();
+        // a computed block around the name cell
+        let mut entry = layout.get_field_config(FieldName::CharacterName);
+        entry.range = "A1:Z10".to_string();
+        entry.computed = Some(true);
+        layout.settings.sheet_field.push(entry);
+
+        let mut update = empty_update();
+        update.character_name = Some("Johanna Schwarz".to_string());
+
+        let result = update.into_value_ranges(&layout, ApiKeyScope::Storyteller);
+
+        assert!(matches!(result, Err(GatewayError::ProtectedRange(_))));
+    }
+
+    #[tokio::test]
+    async fn lists_longer_than_their_range_are_not_written() {
+        let client = client();
+        let mut update = empty_update();
+        update.rituals = Some(
+            (1..=16)
+                .map(|x| RitualUpdateInput {
+                    name: format!("Ritual {}", x),
+                    level: 1,
+                    ritual_type: "Thaumaturgy".to_string(),
+                })
+                .collect(),
+        );
+
+        let result = client
+            .write_data(SHEET.to_string(), update, ApiKeyScope::Storyteller)
+            .await;
+        let player_character = client.parse_data(SHEET.to_string()).await.unwrap();
+
+        assert!(matches!(result, Err(GatewayError::BadRequest(_))));
+        assert_eq!(player_character.rituals.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn damage_is_read_back() {
+        let client = client();
+        let before = client.parse_data(SHEET.to_string()).await.unwrap();
+        let health = before
+            .battle_information
+            .base
+            .health
+            .apply_damage(2, DamageType::Normal);
+
+        client
+            .write_health(SHEET, &health, ApiKeyScope::PlayerWrite)
+            .await
+            .unwrap();
+        let after = client.parse_data(SHEET.to_string()).await.unwrap();
+        let after = &after.battle_information.base.health;
+
+        assert_eq!(after.healthy.boxes, health.healthy.boxes);
+        assert_eq!(after.injured.boxes, health.injured.boxes);
+        assert_eq!(after.incapacitated.boxes, health.incapacitated.boxes);
+    }
+
+    #[tokio::test]
+    async fn health_goes_through_the_protection_check() {
+        let mut layout = SheetLayouts::load("FieldConfig.toml", "layouts")
+            .unwrap()
+            .default_layout()
+            .as_ref()
+            .clone();
+        for entry in layout.settings.sheet_field.iter_mut() {
+            if entry.name == FieldName::GesundheitInjured {
+                entry.storyteller_only = Some(true);
             }
         }
+        let health = client()
+            .parse_data(SHEET.to_string())
+            .await
+            .unwrap()
+            .battle_information
+            .base
+            .health
+            .apply_damage(1, DamageType::Normal);
+
+        let value_ranges = health.value_ranges(&layout);
+        let player = check_value_ranges(&value_ranges, &layout, ApiKeyScope::PlayerWrite);
+        let storyteller = check_value_ranges(&value_ranges, &layout, ApiKeyScope::Storyteller);
+
+        assert!(matches!(player, Err(GatewayError::ProtectedRange(_))));
+        assert!(storyteller.is_ok());
     }
 }
@@ -12,7 +12,7 @@ pub struct Powers {
 }
 
 // Struct for Updating the Powers field
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PowersUpdateInput {
     pub in_clan_disciplines: Option<Vec<DisciplineUpdateInput>>,
     pub out_of_clan_disciplines: Option<Vec<DisciplineUpdateInput>>,
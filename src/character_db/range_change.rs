@@ -0,0 +1,173 @@
+use super::sheet_range::SheetRange;
+use google_sheets4::api::ValueRange;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A range an update writes, with the values of its cells before and after the update
+#[derive(Serialize, Debug)]
+pub struct RangeChange {
+    pub range: String,
+    /// the current values in the shape of the new values, cells the sheet returned no value for are empty
+    pub old_values: Vec<Vec<String>>,
+    pub new_values: Vec<Vec<String>>,
+    pub changed: bool,
+    /// the A1 addresses of the cells whose value changes, the only cells which get written
+    pub changed_cells: Vec<String>,
+}
+
+impl RangeChange {
+    /// a single cell value range for every changed cell
+    pub fn cell_updates(&self) -> Vec<ValueRange> {
+        let start = match self.range.parse::<SheetRange>() {
+            Ok(range) => range,
+            Err(_) => return Vec::new(),
+        };
+
+        changed_positions(&start, &self.old_values, &self.new_values)
+            .into_iter()
+            .map(|(row, column)| ValueRange {
+                major_dimension: Some("ROWS".to_string()),
+                range: Some(cell_address(&start, row, column)),
+                values: Some(vec![vec![self.new_values[row][column].clone()]]),
+            })
+            .collect()
+    }
+}
+
+/// pairs the ranges an update writes with the current values of the same ranges
+pub fn range_changes(planned: &[ValueRange], current: &[ValueRange]) -> Vec<RangeChange> {
+    let current: HashMap<SheetRange, &ValueRange> = current
+        .iter()
+        .filter_map(|value_range| {
+            let range = value_range.range.as_ref()?.parse::<SheetRange>().ok()?;
+            Some((range, value_range))
+        })
+        .collect();
+
+    planned
+        .iter()
+        .map(|value_range| {
+            let range = value_range.range.clone().unwrap_or_default();
+            let start = range.parse::<SheetRange>().ok();
+            let new_values = value_range.values.clone().unwrap_or_default();
+            let current_values = start
+                .and_then(|x| current.get(&x))
+                .and_then(|x| x.values.clone())
+                .unwrap_or_default();
+
+            let old_values: Vec<Vec<String>> = new_values
+                .iter()
+                .enumerate()
+                .map(|(row, new_row)| {
+                    (0..new_row.len())
+                        .map(|column| {
+                            current_values
+                                .get(row)
+                                .and_then(|x| x.get(column))
+                                .cloned()
+                                .unwrap_or_default()
+                        })
+                        .collect()
+                })
+                .collect();
+
+            let changed_cells: Vec<String> = match start {
+                Some(start) => changed_positions(&start, &old_values, &new_values)
+                    .into_iter()
+                    .map(|(row, column)| cell_address(&start, row, column))
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            RangeChange {
+                range,
+                changed: !changed_cells.is_empty(),
+                old_values,
+                new_values,
+                changed_cells,
+            }
+        })
+        .collect()
+}
+
+/// the (row, column) positions within the range whose values differ, both sides have the same shape
+///
+/// Values beyond the range are left out, they belong to other cells of the sheet.
+fn changed_positions(
+    range: &SheetRange,
+    old_values: &[Vec<String>],
+    new_values: &[Vec<String>],
+) -> Vec<(usize, usize)> {
+    let mut positions: Vec<(usize, usize)> = Vec::new();
+    let rows = old_values.iter().zip(new_values).take(range.rows());
+
+    for (row, (old_row, new_row)) in rows.enumerate() {
+        let columns = old_row.iter().zip(new_row).take(range.columns());
+
+        for (column, (old_value, new_value)) in columns.enumerate() {
+            if old_value != new_value {
+                positions.push((row, column));
+            }
+        }
+    }
+
+    positions
+}
+
+/// the A1 address of a cell within a range
+fn cell_address(range: &SheetRange, row: usize, column: usize) -> String {
+    let row = range.start_row + row as u32;
+    let column = range.start_column + column as u32;
+
+    SheetRange {
+        start_row: row,
+        start_column: column,
+        end_row: row,
+        end_column: column,
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value_range(range: &str, values: &[&[&str]]) -> ValueRange {
+        ValueRange {
+            major_dimension: Some("ROWS".to_string()),
+            range: Some(range.to_string()),
+            values: Some(
+                values
+                    .iter()
+                    .map(|row| row.iter().map(|x| x.to_string()).collect())
+                    .collect(),
+            ),
+        }
+    }
+
+    #[test]
+    fn only_changed_cells_are_written() {
+        let planned = [value_range("B2:C3", &[&["a", "b"], &["c", "d"]])];
+        let current = [value_range("B2:C3", &[&["a", "x"], &["c"]])];
+
+        let changes = range_changes(&planned, &current);
+
+        assert_eq!(changes[0].changed_cells, vec!["C2", "C3"]);
+        assert_eq!(changes[0].cell_updates().len(), 2);
+    }
+
+    #[test]
+    fn values_beyond_the_range_are_not_written() {
+        let planned = [value_range("A1:A2", &[&["a", "b"], &["c"], &["d"]])];
+
+        let changes = range_changes(&planned, &[]);
+        let cell_updates: Vec<String> = changes[0]
+            .cell_updates()
+            .into_iter()
+            .filter_map(|x| x.range)
+            .collect();
+
+        assert_eq!(changes[0].changed_cells, vec!["A1", "A2"]);
+        assert_eq!(cell_updates, vec!["A1", "A2"]);
+    }
+}
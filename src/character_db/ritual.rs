@@ -10,7 +10,7 @@ pub struct Ritual {
 }
 
 /// Input for updating a ritual
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RitualUpdateInput {
     pub name: String,
     pub level: u8,
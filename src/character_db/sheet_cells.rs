@@ -0,0 +1,97 @@
+use super::sheet_range::SheetRange;
+use google_sheets4::api::ValueRange;
+use std::collections::HashMap;
+
+/// a single cell value, addressed by zero based (row, column)
+pub type CellUpdate = ((u32, u32), String);
+
+/// The non-empty cells of a single sheet, keyed by zero based (row, column)
+#[derive(Debug, Default, Clone)]
+pub struct SheetCells {
+    cells: HashMap<(u32, u32), String>,
+}
+
+impl SheetCells {
+    pub fn new() -> SheetCells {
+        SheetCells::default()
+    }
+
+    /// sets a single cell, an empty value clears the cell
+    pub fn set(&mut self, position: (u32, u32), value: String) {
+        if value.is_empty() {
+            self.cells.remove(&position);
+        } else {
+            self.cells.insert(position, value);
+        }
+    }
+
+    /// reads a range the same way the google api returns it
+    pub fn read_range(&self, range_string: &str) -> Result<ValueRange, String> {
+        let range: SheetRange = range_string.parse()?;
+        let mut rows: Vec<Vec<String>> = Vec::new();
+
+        for row_index in range.start_row..=range.end_row {
+            let mut row: Vec<String> = (range.start_column..=range.end_column)
+                .map(|column_index| {
+                    self.cells
+                        .get(&(row_index, column_index))
+                        .cloned()
+                        .unwrap_or_default()
+                })
+                .collect();
+
+            // the google api omits trailing empty cells and rows, so do we
+            while row.last().is_some_and(|x| x.is_empty()) {
+                row.pop();
+            }
+
+            rows.push(row);
+        }
+
+        while rows.last().is_some_and(|x| x.is_empty()) {
+            rows.pop();
+        }
+
+        Ok(ValueRange {
+            major_dimension: Some("ROWS".to_string()),
+            range: Some(range_string.to_string()),
+            values: match rows.is_empty() {
+                true => None,
+                false => Some(rows),
+            },
+        })
+    }
+
+    /// writes a range and returns the number of updated cells
+    pub fn write_range(&mut self, value_range: &ValueRange) -> Result<i32, String> {
+        let updates = cell_updates(value_range)?;
+        let updated_cells = updates.len() as i32;
+
+        for (position, value) in updates {
+            self.set(position, value);
+        }
+
+        Ok(updated_cells)
+    }
+}
+
+/// resolves the values of a value range into the absolute cells they are written to
+pub fn cell_updates(value_range: &ValueRange) -> Result<Vec<CellUpdate>, String> {
+    let range: SheetRange = value_range.range.clone().unwrap_or_default().parse()?;
+    let mut result: Vec<CellUpdate> = Vec::new();
+
+    for (row_offset, row) in value_range.values.iter().flatten().enumerate() {
+        for (column_offset, value) in row.iter().enumerate() {
+            let row_index = range.start_row + row_offset as u32;
+            let column_index = range.start_column + column_offset as u32;
+
+            if row_index > range.end_row || column_index > range.end_column {
+                return Err(format!("values exceed the range {}", range));
+            }
+
+            result.push(((row_index, column_index), value.clone()));
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,518 @@
+use super::{
+    background::Background,
+    battle_offense_information::BattleOffenseInformation,
+    discipline::Discipline,
+    flaw::Flaw,
+    health_box::{DamageType, HealthBox},
+    health_track::{HealthTrack, HEALTH_TRACK_BOXES},
+    item::Item,
+    merit::Merit,
+    name_value::NameValue,
+    parse_diagnostic::ParseDiagnostic,
+    ritual::Ritual,
+    sheet_range::SheetRange,
+};
+use crate::{
+    config::{config_client::ConfigClient, field_name::FieldName},
+    gateway_error::GatewayError,
+};
+use google_sheets4::api::ValueRange;
+use regex::{Captures, Regex};
+use std::{any::type_name, collections::HashMap, str::FromStr};
+
+/// Reads the fields of a sheet response, collecting a diagnostic for every cell it can not read
+///
+/// The returned ranges are matched to the fields by their normalized A1 range, so the order of the
+/// response does not matter. Unreadable cells fall back to the default value of the field, only a
+/// missing range is an error.
+pub struct SheetParser<'a> {
+    ranges: HashMap<SheetRange, &'a ValueRange>,
+    sheet_config: &'a ConfigClient,
+    diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl<'a> SheetParser<'a> {
+    pub fn new(data: &'a [ValueRange], sheet_config: &'a ConfigClient) -> SheetParser<'a> {
+        let ranges = data
+            .iter()
+            .filter_map(|value_range| {
+                let range = value_range.range.as_ref()?.parse::<SheetRange>().ok()?;
+                Some((range, value_range))
+            })
+            .collect();
+
+        SheetParser {
+            ranges,
+            sheet_config,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// the diagnostics collected so far
+    pub fn into_diagnostics(self) -> Vec<ParseDiagnostic> {
+        self.diagnostics
+    }
+
+    /// Takes care of retrieving the rows of a field from the sheet response
+    fn values(&self, field_name: FieldName) -> Result<Vec<Vec<String>>, GatewayError> {
+        let config = self.sheet_config.get_field_config(field_name.clone());
+        let entry = config
+            .range
+            .parse::<SheetRange>()
+            .ok()
+            .and_then(|range| self.ranges.get(&range));
+
+        match entry {
+            Some(entry) => Ok(entry.values.clone().unwrap_or_default()),
+            None => Err(GatewayError::MissingRange {
+                field_name,
+                range: config.range,
+            }),
+        }
+    }
+
+    /// records a cell which could not be read
+    fn report(
+        &mut self,
+        field_name: FieldName,
+        row: usize,
+        column: usize,
+        value: &str,
+        expected: String,
+    ) {
+        let cell = cell_address(field_name.clone(), row, column, self.sheet_config);
+        tracing::debug!("could not read cell {} ({}): {:?}", cell, field_name, value);
+
+        self.diagnostics.push(ParseDiagnostic {
+            field_name,
+            cell,
+            value: value.to_string(),
+            expected,
+        });
+    }
+
+    /// parses a number cell, empty cells and `-` are read as the default without a diagnostic
+    fn parse_number<T>(
+        &mut self,
+        row_values: &[String],
+        row: usize,
+        column: usize,
+        field_name: FieldName,
+    ) -> T
+    where
+        T: FromStr + Default,
+    {
+        let value = row_values.get(column).map(|x| x.trim()).unwrap_or_default();
+
+        if value.is_empty() || value == "-" {
+            return T::default();
+        }
+
+        match value.parse::<T>() {
+            Ok(parsed) => parsed,
+            Err(_) => {
+                self.report(field_name, row, column, value, expected_number::<T>());
+                T::default()
+            }
+        }
+    }
+
+    /// reads a single cell
+    pub fn get_value<T>(&mut self, field_name: FieldName) -> Result<T, GatewayError>
+    where
+        T: FromStr + Default,
+    {
+        let values = self.values(field_name.clone())?;
+        let field = values
+            .first()
+            .and_then(|x| x.first())
+            .cloned()
+            .unwrap_or_else(|| "-".to_string());
+
+        match field.parse::<T>() {
+            Ok(parsed) => Ok(parsed),
+            Err(_) => Ok(self.parse_number(&[field], 0, 0, field_name)),
+        }
+    }
+
+    /// extracts a vec
+    pub fn get_value_vec(&mut self, field_name: FieldName) -> Result<Vec<String>, GatewayError> {
+        Ok(self
+            .values(field_name)?
+            .concat()
+            .iter()
+            .filter_map(|x| match x != "-" && !x.is_empty() {
+                true => Some(x.clone()),
+                false => None,
+            })
+            .collect())
+    }
+
+    /// reads a skill specialization
+    pub fn get_skill_specialization(
+        &mut self,
+        field_name: FieldName,
+    ) -> Result<Option<Vec<String>>, GatewayError> {
+        Ok(Some(
+            self.get_value::<String>(field_name)?
+                .split(',')
+                .map(|x| x.trim().to_string())
+                .filter_map(|x| match x != "-" {
+                    true => Some(x),
+                    false => None,
+                })
+                .collect(),
+        ))
+    }
+
+    /// reads the list of disciplines
+    pub fn get_disciplines(
+        &mut self,
+        field_name: FieldName,
+    ) -> Result<Vec<Discipline>, GatewayError> {
+        let mut result: Vec<Discipline> = Vec::new();
+
+        for (row, entry) in self.values(field_name.clone())?.iter().take(6).enumerate() {
+            let name = entry.first().cloned().unwrap_or_default();
+
+            if name != "-" && !name.is_empty() {
+                result.push(Discipline {
+                    name,
+                    value: self.parse_number(entry, row, 7, field_name.clone()),
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// reads the merits of the combined merits and flaws list
+    pub fn get_merits(&mut self, field_name: FieldName) -> Result<Vec<Merit>, GatewayError> {
+        let re = Regex::new(r"^(V(?P<merit_type>.*):|V )(?P<merit_name>.+)$").unwrap();
+        let mut result: Vec<Merit> = Vec::new();
+
+        for (row, entry) in self.values(field_name.clone())?.iter().take(7).enumerate() {
+            let raw_name = entry.first().cloned().unwrap_or_default();
+
+            if raw_name == "-" || raw_name.is_empty() || raw_name.starts_with('N') {
+                continue;
+            }
+
+            let captured: Option<Captures> = re.captures(&raw_name);
+
+            let name_normalized = match captured.as_ref().and_then(|cap| {
+                cap.name("merit_name")
+                    .map(|merit_name| merit_name.as_str().trim().to_string())
+            }) {
+                Some(value) => value,
+                None => raw_name.clone(),
+            };
+
+            let merit_type = match captured.and_then(|cap| {
+                cap.name("merit_type")
+                    .map(|merit_type| merit_type.as_str().trim().to_string())
+            }) {
+                Some(value) => value,
+                None => "General".to_string(),
+            };
+
+            result.push(Merit {
+                name: name_normalized,
+                value: self.parse_number(entry, row, 7, field_name.clone()),
+                merit_type,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// reads the flaws of the combined merits and flaws list
+    pub fn get_flaws(&mut self, field_name: FieldName) -> Result<Vec<Flaw>, GatewayError> {
+        let re = Regex::new(r"^(N(?P<flaw_type>.*):|N )(?P<flaw_name>.+)$").unwrap();
+        let mut result: Vec<Flaw> = Vec::new();
+
+        for (row, entry) in self.values(field_name.clone())?.iter().take(7).enumerate() {
+            let raw_name = entry.first().cloned().unwrap_or_default();
+
+            if raw_name == "-" || !raw_name.starts_with('N') {
+                continue;
+            }
+
+            let captured: Option<Captures> = re.captures(&raw_name);
+
+            let name_normalized = match captured.as_ref().and_then(|cap| {
+                cap.name("flaw_name")
+                    .map(|flaw_name| flaw_name.as_str().trim().to_string())
+            }) {
+                Some(value) => value,
+                None => raw_name.clone(),
+            };
+
+            let flaw_type = match captured.and_then(|cap| {
+                cap.name("flaw_type")
+                    .map(|flaw_type| flaw_type.as_str().trim().to_string())
+            }) {
+                Some(value) => value,
+                None => "General".to_string(),
+            };
+
+            result.push(Flaw {
+                name: name_normalized,
+                value: self.parse_number(entry, row, 7, field_name.clone()),
+                flaw_type,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// reads the list of backgrounds
+    pub fn get_backgrounds(
+        &mut self,
+        field_name: FieldName,
+    ) -> Result<Vec<Background>, GatewayError> {
+        let mut result: Vec<Background> = Vec::new();
+
+        for (row, entry) in self.values(field_name.clone())?.iter().take(9).enumerate() {
+            let name = entry.first().cloned().unwrap_or_default();
+
+            if name.trim() != "" {
+                result.push(Background {
+                    name,
+                    value: self.parse_number(entry, row, 7, field_name.clone()),
+                    description: entry.get(9).cloned().unwrap_or_default(),
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// reads a health track information
+    pub fn get_health_track(&mut self, field_name: FieldName) -> Result<HealthTrack, GatewayError> {
+        let values = self.values(field_name.clone())?;
+        let track: &[String] = values.first().map(|x| x.as_slice()).unwrap_or_default();
+        let base_value: u8 = self.parse_number(track, 0, 5, field_name.clone());
+        let with_boni: u8 = self.parse_number(track, 0, 7, field_name.clone());
+
+        let boxes: Vec<HealthBox> = (0..HEALTH_TRACK_BOXES as usize)
+            .map(|n| {
+                let mark = track.get(n).map(|x| x.as_str()).unwrap_or_default();
+                let health_box = HealthBox::from_mark(mark);
+
+                if health_box == HealthBox::Unknown {
+                    self.report(field_name.clone(), 0, n, mark, expected_damage_mark());
+                }
+
+                health_box
+            })
+            .collect();
+
+        let lost = boxes.iter().filter(|x| x.damage_type().is_some()).count() as u8;
+
+        let remaining: u8 = match lost > with_boni {
+            true => 0,
+            false => with_boni - lost,
+        };
+
+        Ok(HealthTrack {
+            base_value,
+            with_boni,
+            remaining,
+            boxes,
+        })
+    }
+
+    /// reads the non-physical defense pools (social/mental)
+    pub fn get_non_physical_defense_pool(
+        &mut self,
+        field_name: FieldName,
+    ) -> Result<HashMap<u8, u8>, GatewayError> {
+        let mut result: HashMap<u8, u8> = HashMap::new();
+        let values = self
+            .values(field_name.clone())?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        let first_value: u8 = self.parse_number(&values, 0, 0, field_name.clone());
+        result.insert(0, first_value);
+        for n in 2_u8..9_u8 {
+            let value: u8 = self.parse_number(&values, 0, n as usize, field_name.clone());
+            result.insert(n - 1, value);
+        }
+
+        Ok(result)
+    }
+
+    /// reads the different attack pools
+    pub fn get_attack_pools(
+        &mut self,
+        field_name: FieldName,
+    ) -> Result<Vec<BattleOffenseInformation>, GatewayError> {
+        let mut result: Vec<BattleOffenseInformation> = Vec::new();
+
+        for (row, entry) in self.values(field_name.clone())?.iter().take(24).enumerate() {
+            let skill_name: String = entry.first().cloned().unwrap_or_else(|| "-".to_string());
+            let attribute_name: String = entry.get(10).cloned().unwrap_or_else(|| "-".to_string());
+
+            if skill_name.eq("-") || attribute_name.eq("-") {
+                continue;
+            }
+
+            let skill_value: u8 = self.parse_number(entry, row, 7, field_name.clone());
+            let attribute_value: u8 = self.parse_number(entry, row, 14, field_name.clone());
+            let wildcard_name: String = entry.get(17).cloned().unwrap_or_else(|| "-".to_string());
+            let wildcard_value: u8 = self.parse_number(entry, row, 21, field_name.clone());
+            let pool: u8 = self.parse_number(entry, row, 24, field_name.clone());
+            let description: String = entry.get(26).cloned().unwrap_or_default();
+
+            result.push(BattleOffenseInformation {
+                skill: NameValue {
+                    name: skill_name,
+                    value: skill_value,
+                },
+                attribute: NameValue {
+                    name: attribute_name,
+                    value: attribute_value,
+                },
+                wildcard: NameValue {
+                    name: wildcard_name,
+                    value: wildcard_value,
+                },
+                pool,
+                description,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// reads the rituals section
+    pub fn get_rituals(&mut self, field_name: FieldName) -> Result<Vec<Ritual>, GatewayError> {
+        let mut result: Vec<Ritual> = Vec::new();
+        let re = Regex::new(r"^(?P<ritual_type>\w{1})\d{1}(?P<ritual_name>.+)$").unwrap();
+
+        for (row, entry) in self.values(field_name.clone())?.iter().take(15).enumerate() {
+            let ritual_name: String = entry.first().cloned().unwrap_or_else(|| "-".to_string());
+
+            if ritual_name.eq("-") {
+                continue;
+            }
+
+            let level: u8 = self.parse_number(entry, row, 7, field_name.clone());
+            let description: String = entry.get(9).cloned().unwrap_or_else(|| "-".to_string());
+
+            if level.eq(&0) {
+                continue;
+            }
+
+            let captured: Option<Captures> = re.captures(ritual_name.as_str());
+
+            let ritual_type = match captured.as_ref().and_then(|cap| {
+                cap.name("ritual_type")
+                    .and_then(|ritual_type| ritual_type.as_str().trim().chars().next())
+            }) {
+                Some('A') => "Abyssal".to_string(),
+                Some('N') => "Necromancy".to_string(),
+                Some('T') => "Thaumaturgy".to_string(),
+                _ => {
+                    self.report(
+                        field_name.clone(),
+                        row,
+                        0,
+                        &ritual_name,
+                        "a ritual like 'T1 Name' (A, N or T followed by the level)".to_string(),
+                    );
+                    "Unbekannt".to_string()
+                }
+            };
+
+            // names without the type and level prefix are taken as they are
+            let name: String = captured
+                .and_then(|cap| {
+                    cap.name("ritual_name")
+                        .map(|ritual_name| ritual_name.as_str().trim().to_string())
+                })
+                .unwrap_or_else(|| ritual_name.trim().to_string());
+
+            result.push(Ritual {
+                name,
+                level,
+                description,
+                ritual_type,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// reads the items section
+    pub fn get_items(&mut self, field_name: FieldName) -> Result<Vec<Item>, GatewayError> {
+        let mut result: Vec<Item> = Vec::new();
+
+        for item_data in self.values(field_name)?.iter() {
+            let name = item_data.first().cloned().unwrap_or_default();
+
+            if !name.is_empty() {
+                result.push(Item {
+                    name,
+                    trait_1: item_data.get(5).cloned().unwrap_or_default(),
+                    trait_1_description: item_data.get(9).cloned().unwrap_or_default(),
+                    trait_2: item_data.get(13).cloned().unwrap_or_default(),
+                    trait_2_description: item_data.get(17).cloned().unwrap_or_default(),
+                    additional_trait: item_data.get(21).cloned().unwrap_or_default(),
+                    additional_trait_description: item_data.get(25).cloned().unwrap_or_default(),
+                });
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// the A1 address of a cell, relative to the start of the range of a field
+pub fn cell_address(
+    field_name: FieldName,
+    row: usize,
+    column: usize,
+    sheet_config: &ConfigClient,
+) -> String {
+    let range = sheet_config.get_field_config(field_name).range;
+
+    match range.parse::<SheetRange>() {
+        Ok(parsed) => {
+            let cell_row = parsed.start_row + row as u32;
+            let cell_column = parsed.start_column + column as u32;
+
+            SheetRange {
+                start_row: cell_row,
+                start_column: cell_column,
+                end_row: cell_row,
+                end_column: cell_column,
+            }
+            .to_string()
+        }
+        Err(_) => range,
+    }
+}
+
+/// describes the marks of a health box, used in diagnostics
+fn expected_damage_mark() -> String {
+    format!(
+        "an empty box, {} or {}",
+        DamageType::Normal.mark(),
+        DamageType::Aggravated.mark()
+    )
+}
+
+/// describes the numbers a type can hold, used in diagnostics
+fn expected_number<T>() -> String {
+    match type_name::<T>() {
+        "u8" => "a whole number from 0 to 255".to_string(),
+        "u16" => "a whole number from 0 to 65535".to_string(),
+        "i8" => "a whole number from -128 to 127".to_string(),
+        "i16" => "a whole number from -32768 to 32767".to_string(),
+        _ => "a number".to_string(),
+    }
+}
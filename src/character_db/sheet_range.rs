@@ -0,0 +1,117 @@
+use std::{fmt::Display, str::FromStr};
+
+/// A rectangular A1 range (e.g. `D9:D11`), stored as zero based, inclusive coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SheetRange {
+    pub start_row: u32,
+    pub start_column: u32,
+    pub end_row: u32,
+    pub end_column: u32,
+}
+
+impl SheetRange {
+    pub fn rows(&self) -> usize {
+        (self.end_row - self.start_row + 1) as usize
+    }
+
+    pub fn columns(&self) -> usize {
+        (self.end_column - self.start_column + 1) as usize
+    }
+
+    /// whether both ranges share at least one cell
+    pub fn intersects(&self, other: &SheetRange) -> bool {
+        self.start_row <= other.end_row
+            && other.start_row <= self.end_row
+            && self.start_column <= other.end_column
+            && other.start_column <= self.end_column
+    }
+}
+
+impl FromStr for SheetRange {
+    type Err = String;
+
+    /// parses an A1 range, an optional sheet name prefix (`'Sheet 1'!A1:B2`) is ignored
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let without_sheet = match value.rfind('!') {
+            Some(index) => &value[index + 1..],
+            None => value,
+        };
+
+        let (start, end) = match without_sheet.split_once(':') {
+            Some((start, end)) => (start, end),
+            None => (without_sheet, without_sheet),
+        };
+
+        let (start_row, start_column) =
+            parse_cell(start).ok_or_else(|| format!("'{}' is not a valid A1 range", value))?;
+        let (end_row, end_column) =
+            parse_cell(end).ok_or_else(|| format!("'{}' is not a valid A1 range", value))?;
+
+        if end_row < start_row || end_column < start_column {
+            return Err(format!("'{}' ends before it starts", value));
+        }
+
+        Ok(SheetRange {
+            start_row,
+            start_column,
+            end_row,
+            end_column,
+        })
+    }
+}
+
+impl Display for SheetRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let start = format_cell(self.start_row, self.start_column);
+
+        if self.start_row == self.end_row && self.start_column == self.end_column {
+            write!(f, "{}", start)
+        } else {
+            write!(
+                f,
+                "{}:{}",
+                start,
+                format_cell(self.end_row, self.end_column)
+            )
+        }
+    }
+}
+
+/// parses a single cell reference like `AA100` into zero based (row, column)
+fn parse_cell(cell: &str) -> Option<(u32, u32)> {
+    let cell = cell.trim().replace('$', "");
+    let split_at = cell.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = cell.split_at(split_at);
+
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let mut column: u32 = 0;
+    for letter in letters.to_ascii_uppercase().chars() {
+        column = column
+            .checked_mul(26)?
+            .checked_add(letter as u32 - 'A' as u32 + 1)?;
+    }
+
+    let row: u32 = digits.parse().ok()?;
+    if row == 0 {
+        return None;
+    }
+
+    Some((row - 1, column - 1))
+}
+
+/// formats zero based (row, column) coordinates as an A1 cell reference
+fn format_cell(row: u32, column: u32) -> String {
+    let mut letters = String::new();
+    let mut remaining = column + 1;
+
+    while remaining > 0 {
+        let rest = (remaining - 1) % 26;
+        letters.insert(0, char::from(b'A' + rest as u8));
+        remaining = (remaining - 1) / 26;
+    }
+
+    format!("{}{}", letters, row + 1)
+}
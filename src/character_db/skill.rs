@@ -8,7 +8,7 @@ pub struct Skill {
 }
 
 /// Struct for updating a skill
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SkillUpdateInput {
     pub value: Option<u8>,
     pub foci: Option<Vec<String>>,
@@ -36,7 +36,7 @@ pub struct Skills {
 }
 
 /// Struct for Updating the list of skills
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SkillsUpdateInput {
     pub academics: Option<SkillUpdateInput>,
     pub athletics: Option<SkillUpdateInput>,
@@ -0,0 +1,113 @@
+use super::{
+    character_source::CharacterSource,
+    sheet_cells::{cell_updates, CellUpdate, SheetCells},
+    workbook_writer,
+};
+use crate::gateway_error::GatewayError;
+use async_trait::async_trait;
+use calamine::{open_workbook_auto, Reader};
+use google_sheets4::api::ValueRange;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+/// the supported workbook formats, looked up in this order
+const WORKBOOK_EXTENSIONS: [&str; 2] = ["xlsx", "ods"];
+
+/// Character source reading and writing local .xlsx/.ods files
+///
+/// The sheet id is the file name without extension, the ranges refer to the first worksheet.
+pub struct WorkbookSource {
+    directory: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl WorkbookSource {
+    pub fn new(directory: String) -> WorkbookSource {
+        WorkbookSource {
+            directory: PathBuf::from(directory),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// resolves the workbook file belonging to a sheet id
+    fn workbook_path(&self, sheet_id: &str) -> Result<PathBuf, GatewayError> {
+        if sheet_id.is_empty() || sheet_id.starts_with('.') || sheet_id.contains(['/', '\\']) {
+            return Err(GatewayError::SheetNotFound(sheet_id.to_string()));
+        }
+
+        WORKBOOK_EXTENSIONS
+            .iter()
+            .map(|extension| self.directory.join(format!("{}.{}", sheet_id, extension)))
+            .find(|path| path.is_file())
+            .ok_or_else(|| GatewayError::SheetNotFound(sheet_id.to_string()))
+    }
+}
+
+/// reads all cells of the first worksheet of a workbook
+fn read_workbook(path: &Path) -> Result<SheetCells, String> {
+    let mut workbook = open_workbook_auto(path).map_err(|err| err.to_string())?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| format!("{} contains no worksheet", path.display()))?
+        .map_err(|err| err.to_string())?;
+
+    let mut cells = SheetCells::new();
+    let (start_row, start_column) = range.start().unwrap_or_default();
+
+    for (row, column, value) in range.used_cells() {
+        cells.set(
+            (start_row + row as u32, start_column + column as u32),
+            value.to_string(),
+        );
+    }
+
+    Ok(cells)
+}
+
+#[async_trait]
+impl CharacterSource for WorkbookSource {
+    async fn load_ranges(
+        &self,
+        sheet_id: &str,
+        ranges: &[String],
+    ) -> Result<Vec<ValueRange>, GatewayError> {
+        let path = self.workbook_path(sheet_id)?;
+        let ranges = ranges.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            let cells = read_workbook(&path).map_err(GatewayError::SourceFailure)?;
+
+            ranges
+                .iter()
+                .map(|range| cells.read_range(range))
+                .collect::<Result<Vec<ValueRange>, String>>()
+                .map_err(GatewayError::InvalidRange)
+        })
+        .await
+        .map_err(|err| GatewayError::Internal(err.to_string()))?
+    }
+
+    async fn update_ranges(
+        &self,
+        sheet_id: &str,
+        data: Vec<ValueRange>,
+    ) -> Result<i32, GatewayError> {
+        let path = self.workbook_path(sheet_id)?;
+
+        let mut updates: Vec<CellUpdate> = Vec::new();
+        for value_range in &data {
+            updates.extend(cell_updates(value_range).map_err(GatewayError::InvalidRange)?);
+        }
+        let updated_cells = updates.len() as i32;
+
+        // the workbook is rewritten as a whole, so concurrent writes must not overlap
+        let _guard = self.write_lock.lock().await;
+
+        tokio::task::spawn_blocking(move || workbook_writer::write_cells(&path, updates))
+            .await
+            .map_err(|err| GatewayError::Internal(err.to_string()))?
+            .map_err(GatewayError::SourceFailure)?;
+
+        Ok(updated_cells)
+    }
+}
@@ -0,0 +1,1001 @@
+use super::{sheet_cells::CellUpdate, sheet_range::SheetRange};
+use quick_xml::{
+    escape::unescape,
+    events::{attributes::Attribute, BytesEnd, BytesStart, BytesText, Event},
+    Reader, Writer,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{Cursor, Read, Write},
+    path::{Path, PathBuf},
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+/// the cell updates grouped by zero based row and column
+type CellUpdates = BTreeMap<u32, BTreeMap<u32, String>>;
+
+/// writes the passed in cells into the first worksheet of a .xlsx or .ods workbook
+///
+/// Only the worksheet xml is rewritten, styles and all other cells are kept as they are.
+/// Formula results are not recalculated, the spreadsheet application does that on the next save.
+pub fn write_cells(path: &Path, updates: Vec<CellUpdate>) -> Result<(), String> {
+    let mut grouped_updates: CellUpdates = BTreeMap::new();
+    for ((row, column), value) in updates {
+        grouped_updates
+            .entry(row)
+            .or_default()
+            .insert(column, value);
+    }
+
+    let content = fs::read(path).map_err(|err| err.to_string())?;
+    let mut archive = ZipArchive::new(Cursor::new(content)).map_err(|err| err.to_string())?;
+
+    let replaced_files = match path.extension().and_then(|x| x.to_str()) {
+        Some("xlsx") => xlsx_replacements(&mut archive, grouped_updates)?,
+        Some("ods") => {
+            let content = read_archive_file(&mut archive, "content.xml")?;
+            let mut replaced_files = HashMap::new();
+            replaced_files.insert(
+                "content.xml".to_string(),
+                Some(rewrite_ods_content(&content, grouped_updates)?),
+            );
+            replaced_files
+        }
+        _ => return Err(format!("{} is not a supported workbook", path.display())),
+    };
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+    for index in 0..archive.len() {
+        let file = archive.by_index_raw(index).map_err(|err| err.to_string())?;
+
+        match replaced_files.get(file.name()) {
+            Some(Some(content)) => {
+                let name = file.name().to_string();
+                drop(file);
+                writer
+                    .start_file(
+                        name,
+                        SimpleFileOptions::default()
+                            .compression_method(CompressionMethod::Deflated),
+                    )
+                    .map_err(|err| err.to_string())?;
+                writer
+                    .write_all(content.as_bytes())
+                    .map_err(|err| err.to_string())?;
+            }
+            Some(None) => continue,
+            None => writer.raw_copy_file(file).map_err(|err| err.to_string())?,
+        }
+    }
+
+    let result = writer.finish().map_err(|err| err.to_string())?.into_inner();
+
+    // writing to a temporary file first, so a failed write never leaves a broken workbook behind
+    let temporary_path = PathBuf::from(format!("{}.tmp", path.display()));
+    fs::write(&temporary_path, result).map_err(|err| err.to_string())?;
+    fs::rename(&temporary_path, path).map_err(|err| err.to_string())
+}
+
+/// rewrites the first worksheet of a .xlsx workbook, files mapped to `None` are removed
+fn xlsx_replacements(
+    archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+    updates: CellUpdates,
+) -> Result<HashMap<String, Option<String>>, String> {
+    let mut result: HashMap<String, Option<String>> = HashMap::new();
+
+    let workbook = read_archive_file(archive, "xl/workbook.xml")?;
+    let relations = read_archive_file(archive, "xl/_rels/workbook.xml.rels")?;
+    let sheet_path = first_xlsx_sheet_path(&workbook, &relations)?;
+
+    let sheet = read_archive_file(archive, &sheet_path)?;
+    result.insert(sheet_path, Some(rewrite_xlsx_sheet(&sheet, updates)?));
+
+    // the calculation chain may reference formulas we just replaced, excel rebuilds it on load
+    if archive.by_name("xl/calcChain.xml").is_ok() {
+        let content_types = read_archive_file(archive, "[Content_Types].xml")?;
+
+        result.insert("xl/calcChain.xml".to_string(), None);
+        result.insert(
+            "xl/_rels/workbook.xml.rels".to_string(),
+            Some(remove_elements(
+                &relations,
+                b"Relationship",
+                b"Target",
+                "calcChain.xml",
+            )?),
+        );
+        result.insert(
+            "[Content_Types].xml".to_string(),
+            Some(remove_elements(
+                &content_types,
+                b"Override",
+                b"PartName",
+                "/xl/calcChain.xml",
+            )?),
+        );
+    }
+
+    result.insert(
+        "xl/workbook.xml".to_string(),
+        Some(enable_full_calculation(&workbook)?),
+    );
+
+    Ok(result)
+}
+
+/// looks up the archive path of the first worksheet of a .xlsx workbook
+fn first_xlsx_sheet_path(workbook: &str, relations: &str) -> Result<String, String> {
+    let mut relation_id: Option<String> = None;
+    let mut reader = Reader::from_str(workbook);
+
+    while relation_id.is_none() {
+        match reader.read_event().map_err(|err| err.to_string())? {
+            Event::Start(element) | Event::Empty(element)
+                if element.local_name().as_ref() == b"sheet" =>
+            {
+                relation_id = attribute_value(&element, b"r:id");
+            }
+            Event::Eof => return Err("the workbook contains no worksheet".to_string()),
+            _ => (),
+        }
+    }
+
+    let mut reader = Reader::from_str(relations);
+
+    loop {
+        match reader.read_event().map_err(|err| err.to_string())? {
+            Event::Start(element) | Event::Empty(element)
+                if element.local_name().as_ref() == b"Relationship"
+                    && attribute_value(&element, b"Id") == relation_id =>
+            {
+                let target = attribute_value(&element, b"Target").unwrap_or_default();
+
+                return Ok(match target.strip_prefix('/') {
+                    Some(absolute) => absolute.to_string(),
+                    None => format!("xl/{}", target),
+                });
+            }
+            Event::Eof => return Err("the first worksheet could not be resolved".to_string()),
+            _ => (),
+        }
+    }
+}
+
+/// sets `fullCalcOnLoad` so formulas depending on the written cells are refreshed when opened
+fn enable_full_calculation(workbook: &str) -> Result<String, String> {
+    let mut reader = Reader::from_str(workbook);
+    let mut writer = Writer::new(Vec::new());
+
+    loop {
+        let event = reader.read_event().map_err(|err| err.to_string())?;
+
+        match event {
+            Event::Empty(element) if element.local_name().as_ref() == b"calcPr" => {
+                let mut calculation = copy_start(&element, &[b"fullCalcOnLoad"]);
+                calculation.push_attribute(("fullCalcOnLoad", "1"));
+                write_event(&mut writer, Event::Empty(calculation))?;
+            }
+            Event::Eof => break,
+            event => write_event(&mut writer, event)?,
+        }
+    }
+
+    into_string(writer)
+}
+
+/// removes all elements with the passed in name whose attribute ends with the passed in value
+fn remove_elements(
+    content: &str,
+    name: &[u8],
+    attribute: &[u8],
+    value_suffix: &str,
+) -> Result<String, String> {
+    let mut reader = Reader::from_str(content);
+    let mut writer = Writer::new(Vec::new());
+
+    loop {
+        let event = reader.read_event().map_err(|err| err.to_string())?;
+
+        match event {
+            Event::Empty(ref element)
+                if element.local_name().as_ref() == name
+                    && attribute_value(element, attribute)
+                        .is_some_and(|x| x.ends_with(value_suffix)) => {}
+            Event::Eof => break,
+            event => write_event(&mut writer, event)?,
+        }
+    }
+
+    into_string(writer)
+}
+
+/// rewrites the `sheetData` of a .xlsx worksheet
+fn rewrite_xlsx_sheet(sheet: &str, mut updates: CellUpdates) -> Result<String, String> {
+    let mut reader = Reader::from_str(sheet);
+    let mut writer = Writer::new(Vec::new());
+
+    let mut in_sheet_data = false;
+    let mut skipping_cell = false;
+    let mut current_row: Option<u32> = None;
+    let mut row_updates: BTreeMap<u32, String> = BTreeMap::new();
+    let mut next_row: u32 = 0;
+    let mut next_column: u32 = 0;
+
+    loop {
+        let event = reader.read_event().map_err(|err| err.to_string())?;
+
+        if skipping_cell {
+            if matches!(event, Event::End(ref element) if element.local_name().as_ref() == b"c") {
+                skipping_cell = false;
+            }
+            continue;
+        }
+
+        let (element, is_empty) = match event {
+            Event::Start(ref element) => (element.clone(), false),
+            Event::Empty(ref element) => (element.clone(), true),
+            Event::End(ref element) if element.local_name().as_ref() == b"sheetData" => {
+                in_sheet_data = false;
+                write_xlsx_rows(&mut writer, &mut updates, u32::MAX)?;
+                write_event(&mut writer, event)?;
+                continue;
+            }
+            Event::End(ref element)
+                if current_row.is_some() && element.local_name().as_ref() == b"row" =>
+            {
+                let row = current_row.take().unwrap();
+                write_xlsx_cells(&mut writer, row, std::mem::take(&mut row_updates))?;
+                write_event(&mut writer, event)?;
+                continue;
+            }
+            Event::Eof => break,
+            event => {
+                write_event(&mut writer, event)?;
+                continue;
+            }
+        };
+
+        match element.local_name().as_ref() {
+            b"sheetData" if is_empty => {
+                write_event(&mut writer, Event::Start(element.clone()))?;
+                write_xlsx_rows(&mut writer, &mut updates, u32::MAX)?;
+                write_event(&mut writer, Event::End(element.to_end()))?;
+            }
+            b"sheetData" => {
+                in_sheet_data = true;
+                write_event(&mut writer, event)?;
+            }
+            b"row" if in_sheet_data && current_row.is_none() => {
+                let row = attribute_value(&element, b"r")
+                    .and_then(|x| x.parse::<u32>().ok())
+                    .map_or(next_row, |x| x - 1);
+                next_row = row + 1;
+                next_column = 0;
+
+                write_xlsx_rows(&mut writer, &mut updates, row)?;
+
+                match updates.remove(&row) {
+                    Some(cells) => {
+                        // the spans are only an optimization hint and may no longer be accurate
+                        write_event(&mut writer, Event::Start(copy_start(&element, &[b"spans"])))?;
+
+                        if is_empty {
+                            write_xlsx_cells(&mut writer, row, cells)?;
+                            write_event(&mut writer, Event::End(element.to_end()))?;
+                        } else {
+                            current_row = Some(row);
+                            row_updates = cells;
+                        }
+                    }
+                    None => {
+                        current_row = (!is_empty).then_some(row);
+                        write_event(&mut writer, event)?;
+                    }
+                }
+            }
+            b"c" if current_row.is_some() => {
+                let row = current_row.unwrap();
+                let column = attribute_value(&element, b"r")
+                    .and_then(|x| x.parse::<SheetRange>().ok())
+                    .map_or(next_column, |x| x.start_column);
+                next_column = column + 1;
+
+                // new cells left of the current one have to be written first to keep the order
+                let later_cells = row_updates.split_off(&column);
+                let earlier_cells = std::mem::replace(&mut row_updates, later_cells);
+                write_xlsx_cells(&mut writer, row, earlier_cells)?;
+
+                match row_updates.remove(&column) {
+                    Some(value) => {
+                        let style = attribute_value(&element, b"s");
+                        write_xlsx_cell(&mut writer, row, column, style, &value)?;
+                        skipping_cell = !is_empty;
+                    }
+                    None => write_event(&mut writer, event)?,
+                }
+            }
+            _ => write_event(&mut writer, event)?,
+        }
+    }
+
+    into_string(writer)
+}
+
+/// writes all pending rows before the passed in row as new row elements
+fn write_xlsx_rows(
+    writer: &mut Writer<Vec<u8>>,
+    updates: &mut CellUpdates,
+    before_row: u32,
+) -> Result<(), String> {
+    let remaining = updates.split_off(&before_row);
+    let pending = std::mem::replace(updates, remaining);
+
+    for (row, cells) in pending {
+        let mut start = BytesStart::new("row");
+        start.push_attribute(("r", (row + 1).to_string().as_str()));
+        write_event(writer, Event::Start(start))?;
+        write_xlsx_cells(writer, row, cells)?;
+        write_event(writer, Event::End(BytesEnd::new("row")))?;
+    }
+
+    Ok(())
+}
+
+/// writes the passed in cells as new cell elements
+fn write_xlsx_cells(
+    writer: &mut Writer<Vec<u8>>,
+    row: u32,
+    cells: BTreeMap<u32, String>,
+) -> Result<(), String> {
+    for (column, value) in cells {
+        write_xlsx_cell(writer, row, column, None, &value)?;
+    }
+
+    Ok(())
+}
+
+/// writes a single .xlsx cell, numbers are stored as numbers and everything else as inline string
+fn write_xlsx_cell(
+    writer: &mut Writer<Vec<u8>>,
+    row: u32,
+    column: u32,
+    style: Option<String>,
+    value: &str,
+) -> Result<(), String> {
+    let reference = cell_reference(row, column);
+    let mut start = BytesStart::new("c");
+    start.push_attribute(("r", reference.as_str()));
+
+    if let Some(style) = style {
+        start.push_attribute(("s", style.as_str()));
+    }
+
+    if value.is_empty() {
+        return write_event(writer, Event::Empty(start));
+    }
+
+    if is_number(value) {
+        write_event(writer, Event::Start(start))?;
+        write_text_element(writer, "v", value)?;
+    } else {
+        start.push_attribute(("t", "inlineStr"));
+        write_event(writer, Event::Start(start))?;
+        write_event(writer, Event::Start(BytesStart::new("is")))?;
+        write_text_element(writer, "t", value)?;
+        write_event(writer, Event::End(BytesEnd::new("is")))?;
+    }
+
+    write_event(writer, Event::End(BytesEnd::new("c")))
+}
+
+/// rewrites the rows of the first table in an .ods `content.xml`
+fn rewrite_ods_content(content: &str, mut updates: CellUpdates) -> Result<String, String> {
+    let mut reader = Reader::from_str(content);
+    let mut writer = Writer::new(Vec::new());
+
+    let mut table_depth: Option<u32> = None;
+    let mut table_done = false;
+    let mut next_row: u32 = 0;
+    let mut row_events: Vec<Event<'static>> = Vec::new();
+    let mut row_depth: u32 = 0;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|err| err.to_string())?
+            .into_owned();
+
+        // collecting a complete row before deciding how to split it
+        if row_depth > 0 {
+            match event {
+                Event::Start(_) => row_depth += 1,
+                Event::End(_) => row_depth -= 1,
+                _ => (),
+            }
+            row_events.push(event);
+
+            if row_depth == 0 {
+                let events = std::mem::take(&mut row_events);
+                next_row = write_ods_row(&mut writer, events, next_row, &mut updates)?;
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(ref element)
+                if !table_done
+                    && table_depth.is_none()
+                    && element.name().as_ref() == b"table:table" =>
+            {
+                table_depth = Some(0);
+                write_event(&mut writer, event)?;
+            }
+            Event::Start(ref element)
+                if table_depth.is_some() && element.name().as_ref() == b"table:table-row" =>
+            {
+                row_depth = 1;
+                row_events.push(event);
+            }
+            Event::Empty(ref element)
+                if table_depth.is_some() && element.name().as_ref() == b"table:table-row" =>
+            {
+                next_row = write_ods_row(&mut writer, vec![event], next_row, &mut updates)?;
+            }
+            Event::Start(_) if table_depth.is_some() => {
+                table_depth = table_depth.map(|x| x + 1);
+                write_event(&mut writer, event)?;
+            }
+            Event::End(_) if table_depth == Some(0) => {
+                // the sheet has less rows than we need, so the missing ones are appended
+                for (row, cells) in std::mem::take(&mut updates) {
+                    if row > next_row {
+                        let mut filler = BytesStart::new("table:table-row");
+                        filler.push_attribute((
+                            "table:number-rows-repeated",
+                            (row - next_row).to_string().as_str(),
+                        ));
+                        write_event(&mut writer, Event::Start(filler))?;
+                        write_event(
+                            &mut writer,
+                            Event::Empty(BytesStart::new("table:table-cell")),
+                        )?;
+                        write_event(&mut writer, Event::End(BytesEnd::new("table:table-row")))?;
+                    }
+
+                    write_event(
+                        &mut writer,
+                        Event::Start(BytesStart::new("table:table-row")),
+                    )?;
+                    write_ods_cells(&mut writer, Vec::new(), cells)?;
+                    write_event(&mut writer, Event::End(BytesEnd::new("table:table-row")))?;
+                    next_row = row + 1;
+                }
+
+                table_depth = None;
+                table_done = true;
+                write_event(&mut writer, event)?;
+            }
+            Event::End(_) if table_depth.is_some() => {
+                table_depth = table_depth.map(|x| x - 1);
+                write_event(&mut writer, event)?;
+            }
+            Event::Eof => break,
+            event => write_event(&mut writer, event)?,
+        }
+    }
+
+    into_string(writer)
+}
+
+/// writes a (possibly repeated) .ods row, splitting it around the rows that need updates
+fn write_ods_row(
+    writer: &mut Writer<Vec<u8>>,
+    events: Vec<Event<'static>>,
+    first_row: u32,
+    updates: &mut CellUpdates,
+) -> Result<u32, String> {
+    let element = match events.first() {
+        Some(Event::Start(element)) | Some(Event::Empty(element)) => element.clone(),
+        _ => return Err("invalid table row".to_string()),
+    };
+    let repeated: u32 = attribute_value(&element, b"table:number-rows-repeated")
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(1);
+    let end_row = first_row + repeated;
+
+    let remaining = updates.split_off(&end_row);
+    let row_updates = std::mem::replace(updates, remaining);
+
+    if row_updates.is_empty() {
+        for event in events {
+            write_event(writer, event)?;
+        }
+        return Ok(end_row);
+    }
+
+    let cells = split_ods_cells(&events);
+    let mut next_row = first_row;
+
+    for (row, cell_updates) in row_updates {
+        if row > next_row {
+            write_ods_row_copy(writer, &element, row - next_row, &cells)?;
+        }
+
+        write_event(
+            writer,
+            Event::Start(copy_start(&element, &[b"table:number-rows-repeated"])),
+        )?;
+        write_ods_cells(writer, cells.clone(), cell_updates)?;
+        write_event(writer, Event::End(BytesEnd::new("table:table-row")))?;
+        next_row = row + 1;
+    }
+
+    if end_row > next_row {
+        write_ods_row_copy(writer, &element, end_row - next_row, &cells)?;
+    }
+
+    Ok(end_row)
+}
+
+/// writes an unchanged copy of a row with the passed in repeat count
+fn write_ods_row_copy(
+    writer: &mut Writer<Vec<u8>>,
+    element: &BytesStart<'static>,
+    repeated: u32,
+    cells: &[Vec<Event<'static>>],
+) -> Result<(), String> {
+    let mut start = copy_start(element, &[b"table:number-rows-repeated"]);
+    if repeated > 1 {
+        start.push_attribute(("table:number-rows-repeated", repeated.to_string().as_str()));
+    }
+
+    write_event(writer, Event::Start(start))?;
+    for event in cells.iter().flatten() {
+        write_event(writer, event.clone())?;
+    }
+    write_event(writer, Event::End(BytesEnd::new("table:table-row")))
+}
+
+/// splits the content of a buffered row into its top level cells
+fn split_ods_cells(events: &[Event<'static>]) -> Vec<Vec<Event<'static>>> {
+    let mut result: Vec<Vec<Event<'static>>> = Vec::new();
+    let mut depth: u32 = 0;
+
+    if events.len() < 2 {
+        return result;
+    }
+
+    for event in &events[1..events.len() - 1] {
+        if depth == 0 {
+            result.push(Vec::new());
+        }
+
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            _ => (),
+        }
+
+        result.last_mut().unwrap().push(event.clone());
+    }
+
+    result
+}
+
+/// writes the cells of a row, splitting repeated cells around the updated columns
+fn write_ods_cells(
+    writer: &mut Writer<Vec<u8>>,
+    cells: Vec<Vec<Event<'static>>>,
+    mut updates: BTreeMap<u32, String>,
+) -> Result<(), String> {
+    let mut next_column: u32 = 0;
+
+    for cell in cells {
+        let element = match cell.first() {
+            Some(Event::Start(element)) | Some(Event::Empty(element)) => element.clone(),
+            _ => {
+                // whitespace or comments between the cells
+                for event in cell {
+                    write_event(writer, event)?;
+                }
+                continue;
+            }
+        };
+        let repeated: u32 = attribute_value(&element, b"table:number-columns-repeated")
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(1);
+        let end_column = next_column + repeated;
+
+        let remaining = updates.split_off(&end_column);
+        let cell_updates = std::mem::replace(&mut updates, remaining);
+
+        if cell_updates.is_empty() {
+            for event in cell {
+                write_event(writer, event)?;
+            }
+        } else {
+            for (column, value) in cell_updates {
+                if column > next_column {
+                    write_ods_cell_copy(writer, &cell, column - next_column)?;
+                }
+
+                write_ods_cell(writer, &element, &value)?;
+                next_column = column + 1;
+            }
+
+            if end_column > next_column {
+                write_ods_cell_copy(writer, &cell, end_column - next_column)?;
+            }
+        }
+
+        next_column = end_column;
+    }
+
+    // the row ends before the updated columns, so new cells are appended
+    for (column, value) in updates {
+        if column > next_column {
+            let mut filler = BytesStart::new("table:table-cell");
+            filler.push_attribute((
+                "table:number-columns-repeated",
+                (column - next_column).to_string().as_str(),
+            ));
+            write_event(writer, Event::Empty(filler))?;
+        }
+
+        write_ods_cell(writer, &BytesStart::new("table:table-cell"), &value)?;
+        next_column = column + 1;
+    }
+
+    Ok(())
+}
+
+/// writes an unchanged copy of a cell with the passed in repeat count
+fn write_ods_cell_copy(
+    writer: &mut Writer<Vec<u8>>,
+    cell: &[Event<'static>],
+    repeated: u32,
+) -> Result<(), String> {
+    for (index, event) in cell.iter().enumerate() {
+        match event {
+            Event::Start(element) | Event::Empty(element) if index == 0 => {
+                let mut start = copy_start(element, &[b"table:number-columns-repeated"]);
+                if repeated > 1 {
+                    start.push_attribute((
+                        "table:number-columns-repeated",
+                        repeated.to_string().as_str(),
+                    ));
+                }
+
+                match event {
+                    Event::Start(_) => write_event(writer, Event::Start(start))?,
+                    _ => write_event(writer, Event::Empty(start))?,
+                }
+            }
+            event => write_event(writer, event.clone())?,
+        }
+    }
+
+    Ok(())
+}
+
+/// writes a single .ods cell keeping the style of the cell it replaces
+fn write_ods_cell(
+    writer: &mut Writer<Vec<u8>>,
+    element: &BytesStart,
+    value: &str,
+) -> Result<(), String> {
+    let name = String::from_utf8_lossy(element.name().as_ref()).to_string();
+    let mut start = BytesStart::new(name.clone());
+
+    for attribute in element.attributes().flatten() {
+        let key = attribute.key.as_ref();
+        if key == b"table:style-name"
+            || key == b"table:number-columns-spanned"
+            || key == b"table:number-rows-spanned"
+            || key == b"table:content-validation-name"
+        {
+            start.push_attribute(Attribute {
+                key: attribute.key,
+                value: attribute.value.clone(),
+            });
+        }
+    }
+
+    if value.is_empty() {
+        return write_event(writer, Event::Empty(start));
+    }
+
+    if is_number(value) {
+        start.push_attribute(("office:value-type", "float"));
+        start.push_attribute(("office:value", value));
+    } else {
+        start.push_attribute(("office:value-type", "string"));
+    }
+
+    write_event(writer, Event::Start(start))?;
+    write_text_element(writer, "text:p", value)?;
+    write_event(writer, Event::End(BytesEnd::new(name)))
+}
+
+/// reads a file of the archive as string
+fn read_archive_file(
+    archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+    name: &str,
+) -> Result<String, String> {
+    let mut file = archive
+        .by_name(name)
+        .map_err(|err| format!("{}: {}", name, err))?;
+    let mut content = String::new();
+    file.read_to_string(&mut content)
+        .map_err(|err| format!("{}: {}", name, err))?;
+
+    Ok(content)
+}
+
+/// reads an unescaped attribute value
+fn attribute_value(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key.as_ref() == name)
+        .and_then(|attribute| {
+            let raw = String::from_utf8(attribute.value.to_vec()).ok()?;
+            unescape(&raw).ok().map(|value| value.to_string())
+        })
+}
+
+/// copies an element start without the passed in attributes
+fn copy_start(element: &BytesStart, skipped_attributes: &[&[u8]]) -> BytesStart<'static> {
+    let name = String::from_utf8_lossy(element.name().as_ref()).to_string();
+    let mut result = BytesStart::new(name);
+
+    for attribute in element.attributes().flatten() {
+        if !skipped_attributes.contains(&attribute.key.as_ref()) {
+            result.push_attribute(Attribute {
+                key: attribute.key,
+                value: attribute.value.clone(),
+            });
+        }
+    }
+
+    result.into_owned()
+}
+
+fn write_text_element(writer: &mut Writer<Vec<u8>>, name: &str, value: &str) -> Result<(), String> {
+    write_event(writer, Event::Start(BytesStart::new(name)))?;
+    write_event(writer, Event::Text(BytesText::new(value)))?;
+    write_event(writer, Event::End(BytesEnd::new(name)))
+}
+
+fn write_event(writer: &mut Writer<Vec<u8>>, event: Event) -> Result<(), String> {
+    writer.write_event(event).map_err(|err| err.to_string())
+}
+
+fn into_string(writer: Writer<Vec<u8>>) -> Result<String, String> {
+    String::from_utf8(writer.into_inner()).map_err(|err| err.to_string())
+}
+
+fn cell_reference(row: u32, column: u32) -> String {
+    SheetRange {
+        start_row: row,
+        start_column: column,
+        end_row: row,
+        end_column: column,
+    }
+    .to_string()
+}
+
+/// mirrors the `USER_ENTERED` behavior of google, values looking like numbers are stored as numbers
+fn is_number(value: &str) -> bool {
+    value.parse::<f64>().is_ok_and(|x| x.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::{open_workbook_auto, Data, Reader as _};
+
+    const XLSX_SHEET: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="2" spans="1:3"><c r="B2" s="1" t="inlineStr"><is><t>old</t></is></c><c r="C2"><v>7</v></c></row><row r="5"><c r="D5"><f>C2*2</f><v>14</v></c></row></sheetData></worksheet>"#;
+
+    const ODS_CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.2"><office:body><office:spreadsheet><table:table table:name="Sheet1"><table:table-row table:number-rows-repeated="3"><table:table-cell table:number-columns-repeated="4"/></table:table-row><table:table-row><table:table-cell/><table:table-cell office:value-type="string"><text:p>old</text:p></table:table-cell><table:table-cell table:number-columns-repeated="3" office:value-type="float" office:value="5"><text:p>5</text:p></table:table-cell></table:table-row></table:table></office:spreadsheet></office:body></office:document-content>"#;
+
+    /// a workbook in the temp directory, removed again when dropped
+    struct TestWorkbook(PathBuf);
+
+    impl Drop for TestWorkbook {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn create_workbook(name: &str, files: &[(&str, &str)]) -> TestWorkbook {
+        let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+        for (file_name, content) in files {
+            writer
+                .start_file(*file_name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+
+        fs::write(&path, writer.finish().unwrap().into_inner()).unwrap();
+
+        TestWorkbook(path)
+    }
+
+    fn xlsx_workbook(name: &str) -> TestWorkbook {
+        create_workbook(
+            name,
+            &[
+                (
+                    "[Content_Types].xml",
+                    r#"<?xml version="1.0" encoding="UTF-8"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/calcChain.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.calcChain+xml"/></Types>"#,
+                ),
+                (
+                    "_rels/.rels",
+                    r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+                ),
+                (
+                    "xl/workbook.xml",
+                    r#"<?xml version="1.0" encoding="UTF-8"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets><calcPr calcId="0"/></workbook>"#,
+                ),
+                (
+                    "xl/_rels/workbook.xml.rels",
+                    r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/calcChain" Target="calcChain.xml"/></Relationships>"#,
+                ),
+                ("xl/worksheets/sheet1.xml", XLSX_SHEET),
+                (
+                    "xl/calcChain.xml",
+                    r#"<?xml version="1.0" encoding="UTF-8"?><calcChain xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><c r="D5" i="1"/></calcChain>"#,
+                ),
+            ],
+        )
+    }
+
+    fn ods_workbook(name: &str) -> TestWorkbook {
+        create_workbook(
+            name,
+            &[
+                ("mimetype", "application/vnd.oasis.opendocument.spreadsheet"),
+                (
+                    "META-INF/manifest.xml",
+                    r#"<?xml version="1.0" encoding="UTF-8"?><manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2"><manifest:file-entry manifest:full-path="/" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/></manifest:manifest>"#,
+                ),
+                ("content.xml", ODS_CONTENT),
+            ],
+        )
+    }
+
+    /// the cells of the first worksheet as strings, keyed by zero based row and column
+    fn read_cells(path: &Path) -> BTreeMap<(u32, u32), String> {
+        let mut workbook = open_workbook_auto(path).unwrap();
+        let range = workbook.worksheet_range_at(0).unwrap().unwrap();
+        let (start_row, start_column) = range.start().unwrap_or_default();
+
+        range
+            .used_cells()
+            .filter(|(_, _, value)| !matches!(value, Data::Empty))
+            .map(|(row, column, value)| {
+                (
+                    (start_row + row as u32, start_column + column as u32),
+                    value.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    fn update(row: u32, column: u32, value: &str) -> CellUpdate {
+        ((row, column), value.to_string())
+    }
+
+    fn archive_names(path: &Path) -> Vec<String> {
+        let archive = ZipArchive::new(Cursor::new(fs::read(path).unwrap())).unwrap();
+        archive.file_names().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn xlsx_cells_are_replaced_and_added() {
+        let workbook = xlsx_workbook("replace.xlsx");
+
+        write_cells(
+            &workbook.0,
+            vec![
+                // before the existing cells of the row, in between and after them
+                update(1, 0, "first"),
+                update(1, 1, "new"),
+                update(1, 3, "12"),
+                // rows before, between and after the existing rows
+                update(0, 2, "top"),
+                update(3, 0, "middle"),
+                update(9, 1, "bottom"),
+            ],
+        )
+        .unwrap();
+
+        let cells = read_cells(&workbook.0);
+
+        assert_eq!(cells[&(1, 0)], "first");
+        assert_eq!(cells[&(1, 1)], "new");
+        assert_eq!(cells[&(1, 2)], "7");
+        assert_eq!(cells[&(1, 3)], "12");
+        assert_eq!(cells[&(0, 2)], "top");
+        assert_eq!(cells[&(3, 0)], "middle");
+        assert_eq!(cells[&(4, 3)], "14");
+        assert_eq!(cells[&(9, 1)], "bottom");
+    }
+
+    #[test]
+    fn xlsx_keeps_styles_and_drops_the_calculation_chain() {
+        let workbook = xlsx_workbook("styles.xlsx");
+
+        write_cells(&workbook.0, vec![update(1, 1, "")]).unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(fs::read(&workbook.0).unwrap())).unwrap();
+        let sheet = read_archive_file(&mut archive, "xl/worksheets/sheet1.xml").unwrap();
+        let workbook_xml = read_archive_file(&mut archive, "xl/workbook.xml").unwrap();
+        let content_types = read_archive_file(&mut archive, "[Content_Types].xml").unwrap();
+
+        assert!(sheet.contains(r#"<c r="B2" s="1"/>"#));
+        assert!(workbook_xml.contains(r#"fullCalcOnLoad="1""#));
+        assert!(!content_types.contains("calcChain"));
+        assert!(!archive_names(&workbook.0).contains(&"xl/calcChain.xml".to_string()));
+        assert!(!read_cells(&workbook.0).contains_key(&(1, 1)));
+    }
+
+    #[test]
+    fn ods_repeated_rows_and_cells_are_split() {
+        let workbook = ods_workbook("repeated.ods");
+
+        write_cells(
+            &workbook.0,
+            vec![
+                // inside the repeated empty rows
+                update(1, 2, "inside"),
+                // before the existing cells of a row and within repeated cells
+                update(3, 0, "first"),
+                update(3, 3, "6"),
+                // after the last row and column
+                update(6, 5, "appended"),
+            ],
+        )
+        .unwrap();
+
+        let cells = read_cells(&workbook.0);
+
+        assert_eq!(cells[&(1, 2)], "inside");
+        assert!(!cells.contains_key(&(0, 2)));
+        assert!(!cells.contains_key(&(2, 2)));
+        assert_eq!(cells[&(3, 0)], "first");
+        assert_eq!(cells[&(3, 1)], "old");
+        assert_eq!(cells[&(3, 2)], "5");
+        assert_eq!(cells[&(3, 3)], "6");
+        assert_eq!(cells[&(3, 4)], "5");
+        assert_eq!(cells[&(6, 5)], "appended");
+        assert_eq!(cells.len(), 7);
+    }
+
+    #[test]
+    fn ods_keeps_the_other_files() {
+        let workbook = ods_workbook("files.ods");
+
+        write_cells(&workbook.0, vec![update(0, 0, "a")]).unwrap();
+
+        assert_eq!(
+            archive_names(&workbook.0),
+            vec!["mimetype", "META-INF/manifest.xml", "content.xml"]
+        );
+        assert_eq!(read_cells(&workbook.0)[&(0, 0)], "a");
+    }
+
+    #[test]
+    fn other_files_are_rejected() {
+        let workbook = create_workbook("unsupported.csv", &[("a.txt", "a")]);
+
+        assert!(write_cells(&workbook.0, vec![update(0, 0, "a")]).is_err());
+    }
+}
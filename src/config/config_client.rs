@@ -1,8 +1,31 @@
 use config::Config;
+use std::env;
+use strum::IntoEnumIterator;
 
 use super::{
     field_config::FieldConfig, field_config_entry::FieldConfigEntry, field_name::FieldName,
 };
+use crate::character_db::sheet_range::SheetRange;
+
+/// the file the field configuration is read from, unless `FIELD_CONFIG` says otherwise
+pub const DEFAULT_CONFIG_PATH: &str = "FieldConfig.toml";
+
+/// fields whose writers clear the whole range and therefore need the number of rows
+const FIELDS_REQUIRING_RANGE_LENGTH: [FieldName; 8] = [
+    FieldName::MeritsFlawsName,
+    FieldName::Backgrounds,
+    FieldName::Rituale,
+    FieldName::Items,
+    FieldName::AngriffsPoolsFertigkeit,
+    FieldName::AngriffsPoolsAttribut,
+    FieldName::AngriffsPoolsJoker,
+    FieldName::AngriffsPoolsBeschreibung,
+];
+
+/// the path of the field configuration, taken from the `FIELD_CONFIG` env variable
+pub fn config_path() -> String {
+    env::var("FIELD_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string())
+}
 
 #[derive(Clone)]
 pub struct ConfigClient {
@@ -10,20 +33,34 @@ pub struct ConfigClient {
 }
 
 impl ConfigClient {
-    pub fn new() -> ConfigClient {
+    /// reads and validates the field configuration, the error is a readable report of all problems
+    pub fn load(path: &str) -> Result<ConfigClient, String> {
         let settings: FieldConfig = Config::builder()
-            .add_source(config::File::with_name("FieldConfig.toml"))
+            .add_source(config::File::with_name(path))
             .build()
-            .unwrap()
-            .try_deserialize::<FieldConfig>()
-            .unwrap();
+            .and_then(|x| x.try_deserialize::<FieldConfig>())
+            .map_err(|err| format!("{} could not be read: {}", path, err))?;
 
-        ConfigClient { settings }
+        let problems = validate(&settings);
+
+        if !problems.is_empty() {
+            return Err(format!(
+                "{} is invalid:\n{}",
+                path,
+                problems
+                    .iter()
+                    .map(|x| format!("  - {}", x))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ));
+        }
+
+        Ok(ConfigClient { settings })
     }
 
     pub fn get_field_config(&self, field_name: FieldName) -> FieldConfigEntry {
-        let sheet_fields: Vec<FieldConfigEntry> = self.settings.sheet_field.clone();
-        sheet_fields
+        self.settings
+            .sheet_field
             .iter()
             .find(|&x| -> bool { x.name == field_name })
             .unwrap()
@@ -32,7 +69,128 @@ impl ConfigClient {
 
     pub fn get_field_config_sorted(&self) -> Vec<FieldConfigEntry> {
         let mut sheet_fields = self.settings.sheet_field.clone();
-        sheet_fields.sort_by(|a, b| a.position.cmp(&b.position));
+        // entries without a position keep the order of the config file and come last
+        sheet_fields.sort_by_key(|a| a.position.unwrap_or(u8::MAX));
         sheet_fields
     }
 }
+
+/// checks the field configuration and returns every problem found
+pub fn validate(settings: &FieldConfig) -> Vec<String> {
+    let mut problems: Vec<String> = Vec::new();
+    let entries = &settings.sheet_field;
+
+    // every field has to be configured exactly once
+    for field_name in FieldName::iter() {
+        match entries.iter().filter(|x| x.name == field_name).count() {
+            0 => problems.push(format!("{} is not configured", field_name)),
+            1 => {}
+            count => problems.push(format!("{} is configured {} times", field_name, count)),
+        }
+    }
+
+    // the read fields are requested in the order of their positions, so a position is used once
+    for entry in entries
+        .iter()
+        .filter(|x| !x.exclude_on_read.unwrap_or(false))
+    {
+        let position = match entry.position {
+            Some(position) => position,
+            None => continue,
+        };
+        let first = entries
+            .iter()
+            .filter(|x| !x.exclude_on_read.unwrap_or(false))
+            .find(|x| x.position == Some(position))
+            .map(|x| &x.name);
+
+        if first != Some(&entry.name) {
+            problems.push(format!(
+                "{}: position {} is already used by {}",
+                entry.name,
+                position,
+                first.map(|x| x.to_string()).unwrap_or_default()
+            ));
+        }
+    }
+
+    for entry in entries {
+        let range = match entry.range.parse::<SheetRange>() {
+            Ok(range) => range,
+            Err(err) => {
+                problems.push(format!("{}: {}", entry.name, err));
+                continue;
+            }
+        };
+
+        let rows = range.end_row - range.start_row + 1;
+
+        match entry.range_length {
+            Some(range_length) if range_length as u32 != rows => problems.push(format!(
+                "{}: range_length is {} but the range {} has {} rows",
+                entry.name, range_length, entry.range, rows
+            )),
+            None if FIELDS_REQUIRING_RANGE_LENGTH.contains(&entry.name) => problems.push(format!(
+                "{}: range_length is missing, it has to be the number of rows of {}",
+                entry.name, entry.range
+            )),
+            _ => {}
+        }
+
+        if entry.computed == Some(true) && entry.writable == Some(true) {
+            problems.push(format!(
+                "{}: a computed range can not be writable",
+                entry.name
+            ));
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> FieldConfig {
+        ConfigClient::load(DEFAULT_CONFIG_PATH).unwrap().settings
+    }
+
+    #[test]
+    fn the_shipped_config_is_valid() {
+        assert!(validate(&settings()).is_empty());
+    }
+
+    #[test]
+    fn read_fields_may_not_share_a_position() {
+        let mut settings = settings();
+        let position = settings.sheet_field[0].position;
+        settings
+            .sheet_field
+            .iter_mut()
+            .find(|x| x.name == FieldName::Clan)
+            .unwrap()
+            .position = position;
+
+        let problems = validate(&settings);
+
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("Clan: position"));
+    }
+
+    #[test]
+    fn fields_without_a_position_or_not_read_are_not_checked() {
+        let mut settings = settings();
+        for entry in settings
+            .sheet_field
+            .iter_mut()
+            .filter(|x| x.exclude_on_read == Some(true))
+        {
+            entry.position = Some(0);
+        }
+        settings.sheet_field[1].position = None;
+        settings.sheet_field[2].position = None;
+
+        assert!(validate(&settings).is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use super::field_config_entry::FieldConfigEntry;
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub struct FieldConfig {
     pub sheet_field: Vec<FieldConfigEntry>,
 }
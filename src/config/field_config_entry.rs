@@ -1,11 +1,29 @@
 use super::field_name::FieldName;
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
 pub struct FieldConfigEntry {
     pub name: FieldName,
-    pub position: u8,
+    /// only used to order the requested ranges, the response is matched by range
+    pub position: Option<u8>,
     pub range: String,
+    /// the number of rows of the range
     pub range_length: Option<u8>,
     pub exclude_on_read: Option<bool>,
+    /// whether the gateway may write the range, defaults to true
+    pub writable: Option<bool>,
+    /// the range holds a formula of the sheet and is never written
+    pub computed: Option<bool>,
+    /// only storyteller keys may write the range
+    pub storyteller_only: Option<bool>,
+}
+
+impl FieldConfigEntry {
+    pub fn is_writable(&self) -> bool {
+        self.writable.unwrap_or(true) && !self.computed.unwrap_or(false)
+    }
+
+    pub fn is_storyteller_only(&self) -> bool {
+        self.storyteller_only.unwrap_or(false)
+    }
 }
@@ -1,7 +1,7 @@
-use serde::Deserialize;
-use strum::Display;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
 
-#[derive(Debug, Deserialize, Display, Eq, PartialEq, Clone)]
+#[derive(Debug, Deserialize, Serialize, Display, EnumIter, Eq, PartialEq, Clone)]
 pub enum FieldName {
     CharacterName,
     PlayerName,
@@ -84,6 +84,10 @@ pub enum FieldName {
     VerteidigungSozial,
     VerteidigungMental,
     AngriffsPools,
+    AngriffsPoolsFertigkeit,
+    AngriffsPoolsAttribut,
+    AngriffsPoolsJoker,
+    AngriffsPoolsBeschreibung,
     Rituale,
     Items,
 }
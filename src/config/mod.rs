@@ -2,3 +2,4 @@ pub mod config_client;
 pub mod field_config;
 pub mod field_config_entry;
 pub mod field_name;
+pub mod sheet_layouts;
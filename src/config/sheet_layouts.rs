@@ -0,0 +1,119 @@
+use super::{config_client::ConfigClient, field_name::FieldName};
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// the directory holding the layouts of older sheet versions, unless `LAYOUT_DIRECTORY` says otherwise
+pub const DEFAULT_LAYOUT_DIRECTORY: &str = "layouts";
+
+/// the path of the layout directory, taken from the `LAYOUT_DIRECTORY` env variable
+pub fn layout_directory() -> String {
+    env::var("LAYOUT_DIRECTORY").unwrap_or_else(|_| DEFAULT_LAYOUT_DIRECTORY.to_string())
+}
+
+/// The field configurations of all known sheet versions
+///
+/// A layout file is named after the value of the `VersionSheet` cell, e.g. `layouts/3.1.toml`.
+/// Sheets with an unknown version are read with the default configuration.
+pub struct SheetLayouts {
+    default_layout: Arc<ConfigClient>,
+    layouts: HashMap<String, Arc<ConfigClient>>,
+}
+
+impl SheetLayouts {
+    /// loads and validates the default configuration and every layout in the directory
+    pub fn load(config_path: &str, layout_directory: &str) -> Result<SheetLayouts, String> {
+        let mut reports: Vec<String> = Vec::new();
+        let default_layout = match ConfigClient::load(config_path) {
+            Ok(layout) => Some(layout),
+            Err(report) => {
+                reports.push(report);
+                None
+            }
+        };
+        let mut layouts: HashMap<String, Arc<ConfigClient>> = HashMap::new();
+
+        for path in layout_files(layout_directory)? {
+            let version = match path.file_stem().and_then(|x| x.to_str()) {
+                Some(version) => version.to_string(),
+                None => continue,
+            };
+
+            match ConfigClient::load(&path.to_string_lossy()) {
+                Ok(layout) => {
+                    layouts.insert(version, Arc::new(layout));
+                }
+                Err(report) => reports.push(report),
+            }
+        }
+
+        // the version cell is read with the default layout, so no layout may move it
+        if let Some(default_layout) = &default_layout {
+            let version_range = default_layout
+                .get_field_config(FieldName::VersionSheet)
+                .range;
+
+            for (version, layout) in &layouts {
+                let range = layout.get_field_config(FieldName::VersionSheet).range;
+
+                if range != version_range {
+                    reports.push(format!(
+                        "layout {} is invalid:\n  - VersionSheet is at {} but has to stay at {}",
+                        version, range, version_range
+                    ));
+                }
+            }
+        }
+
+        match default_layout {
+            Some(default_layout) if reports.is_empty() => Ok(SheetLayouts {
+                default_layout: Arc::new(default_layout),
+                layouts,
+            }),
+            _ => Err(reports.join("\n")),
+        }
+    }
+
+    /// the layout of the current sheet version, also used to read the version cell
+    pub fn default_layout(&self) -> Arc<ConfigClient> {
+        self.default_layout.clone()
+    }
+
+    /// the layout matching the version cell, the default layout for unknown versions
+    pub fn for_version(&self, version: &str) -> Arc<ConfigClient> {
+        match self.layouts.get(version.trim()) {
+            Some(layout) => layout.clone(),
+            None => self.default_layout.clone(),
+        }
+    }
+
+    /// the versions with their own layout
+    pub fn versions(&self) -> Vec<String> {
+        let mut versions: Vec<String> = self.layouts.keys().cloned().collect();
+        versions.sort();
+        versions
+    }
+}
+
+/// the toml files in the layout directory, a missing directory means there are no layouts
+fn layout_files(layout_directory: &str) -> Result<Vec<PathBuf>, String> {
+    let directory = Path::new(layout_directory);
+
+    if !directory.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(directory)
+        .map_err(|err| format!("{} could not be read: {}", layout_directory, err))?;
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|x| x.path()))
+        .filter(|path| path.extension().is_some_and(|x| x == "toml"))
+        .collect();
+    paths.sort();
+
+    Ok(paths)
+}
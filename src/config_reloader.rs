@@ -0,0 +1,138 @@
+use crate::{
+    api_key_client::ApiKeyClient, app_state::AppState, config::sheet_layouts::SheetLayouts,
+    gateway_error::GatewayError,
+};
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+/// editors write files in several steps, so a reload waits until the changes settled
+const RELOAD_DELAY: Duration = Duration::from_millis(500);
+
+/// the outcome of a successful reload
+#[derive(Serialize, Debug)]
+pub struct ConfigReloadResult {
+    pub versions: Vec<String>,
+    pub invalidated_cache_entries: usize,
+}
+
+/// reloads and validates the layouts, swaps them in and removes the cached characters whose layout changed
+///
+/// An invalid configuration is rejected and the current one stays active.
+pub async fn reload_config(state: &AppState) -> Result<ConfigReloadResult, GatewayError> {
+    let config_path = state.config_path.clone();
+    let layout_directory = state.layout_directory.clone();
+
+    let new_layouts =
+        tokio::task::spawn_blocking(move || SheetLayouts::load(&config_path, &layout_directory))
+            .await
+            .map_err(|err| GatewayError::Internal(err.to_string()))?
+            .map_err(|report| {
+                tracing::error!("keeping the current configuration: {}", report);
+                GatewayError::InvalidConfig(report)
+            })?;
+
+    let versions = new_layouts.versions();
+    let new_layouts = Arc::new(new_layouts);
+    let old_layouts = state.layouts.swap(new_layouts.clone());
+    tracing::info!(
+        "configuration reloaded, layouts for versions: {:?}",
+        versions
+    );
+
+    let mut api_key_client = ApiKeyClient::new(state.redis_connection.clone());
+    let invalidated_cache_entries = api_key_client
+        .invalidate_cached_characters(|player_character| {
+            let version = &player_character.version_sheet;
+            old_layouts.for_version(version).settings != new_layouts.for_version(version).settings
+        })
+        .await?;
+    tracing::debug!("removed {} cached characters", invalidated_cache_entries);
+
+    Ok(ConfigReloadResult {
+        versions,
+        invalidated_cache_entries,
+    })
+}
+
+/// watches the config file and the layout directory and reloads the configuration on changes
+pub fn watch_config(state: AppState) {
+    let config_file = fs::canonicalize(&state.config_path).ok();
+    let layout_directory = fs::canonicalize(&state.layout_directory).ok();
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<()>();
+
+    let watched_config_file = config_file.clone();
+    let watched_layout_directory = layout_directory.clone();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let is_relevant = event.is_ok_and(|event| {
+            !event.kind.is_access()
+                && event.paths.iter().any(|path| {
+                    is_config_file(
+                        path,
+                        watched_config_file.as_deref(),
+                        watched_layout_directory.as_deref(),
+                    )
+                })
+        });
+
+        if is_relevant {
+            let _ = sender.send(());
+        }
+    });
+
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            tracing::error!("the configuration will not be reloaded on changes: {}", err);
+            return;
+        }
+    };
+
+    // files are often replaced instead of changed, so the directories are watched
+    let directories: Vec<PathBuf> = config_file
+        .as_deref()
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .into_iter()
+        .chain(layout_directory)
+        .collect();
+
+    for directory in directories {
+        if let Err(err) = watcher.watch(&directory, RecursiveMode::NonRecursive) {
+            tracing::error!("could not watch {}: {}", directory.display(), err);
+        }
+    }
+
+    tokio::spawn(async move {
+        // the watcher stops when it is dropped
+        let _watcher = watcher;
+
+        while receiver.recv().await.is_some() {
+            tokio::time::sleep(RELOAD_DELAY).await;
+            while receiver.try_recv().is_ok() {}
+
+            if let Err(err) = reload_config(&state).await {
+                tracing::error!("reloading the configuration failed: {}", err);
+            }
+        }
+    });
+}
+
+/// whether a changed file is the config file or a layout
+fn is_config_file(
+    path: &Path,
+    config_file: Option<&Path>,
+    layout_directory: Option<&Path>,
+) -> bool {
+    let is_layout = path
+        .parent()
+        .is_some_and(|parent| Some(parent) == layout_directory)
+        && path.extension().is_some_and(|x| x == "toml");
+
+    Some(path) == config_file || is_layout
+}
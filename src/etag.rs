@@ -0,0 +1,78 @@
+use crate::{character_db::player_character::PlayerCharacter, gateway_error::GatewayError};
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use hyper::{
+    header::{ETAG, IF_MATCH, IF_NONE_MATCH},
+    HeaderMap, StatusCode,
+};
+use sha2::{Digest, Sha256};
+use std::env;
+
+/// a strong etag of the character, its json is normalized first so the order of maps does not matter
+pub fn character_etag(player_character: &PlayerCharacter) -> Result<String, GatewayError> {
+    let normalized = serde_json::to_value(player_character)
+        .and_then(|x| serde_json::to_string(&x))
+        .map_err(|err| GatewayError::Internal(err.to_string()))?;
+    let hash = hex::encode(Sha256::digest(normalized.as_bytes()));
+
+    Ok(format!("\"{}\"", &hash[..32]))
+}
+
+/// whether a list of etags like `"a", W/"b"` or `*` contains the etag
+pub fn etag_matches(header_value: &str, etag: &str) -> bool {
+    header_value
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// the `If-None-Match` header, the client already has the character if it matches
+pub fn if_none_match(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(IF_NONE_MATCH)
+        .and_then(|x| x.to_str().ok())
+        .map(str::to_string)
+}
+
+/// the `If-Match` header of an update, required when `REQUIRE_IF_MATCH` is set to true
+pub fn if_match(headers: &HeaderMap) -> Result<Option<String>, GatewayError> {
+    let value = headers
+        .get(IF_MATCH)
+        .and_then(|x| x.to_str().ok())
+        .map(str::to_string);
+    let required = env::var("REQUIRE_IF_MATCH").is_ok_and(|x| x == "true");
+
+    match value {
+        None if required => Err(GatewayError::PreconditionRequired),
+        value => Ok(value),
+    }
+}
+
+/// rejects the update if the character changed since the client loaded it
+pub fn check_if_match(
+    if_match: &str,
+    player_character: &PlayerCharacter,
+) -> Result<(), GatewayError> {
+    let etag = character_etag(player_character)?;
+
+    match etag_matches(if_match, &etag) {
+        true => Ok(()),
+        false => Err(GatewayError::PreconditionFailed(etag)),
+    }
+}
+
+/// the character along with its etag, `304 Not Modified` if the client already has this version
+pub fn character_response(
+    if_none_match: Option<String>,
+    player_character: PlayerCharacter,
+) -> Result<Response, GatewayError> {
+    let etag = character_etag(&player_character)?;
+
+    if if_none_match.is_some_and(|x| etag_matches(&x, &etag)) {
+        return Ok((StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response());
+    }
+
+    Ok(([(ETAG, etag)], Json(player_character)).into_response())
+}
@@ -0,0 +1,260 @@
+use crate::config::field_name::FieldName;
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use hyper::StatusCode;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// All errors the gateway reports to its clients
+#[derive(Debug)]
+pub enum GatewayError {
+    /// the api key is not mapped to a character sheet
+    UnknownApiKey,
+    /// the admin token is missing or wrong, or no admin token is configured
+    Unauthorized(String),
+    /// the api key may not change these paths of the character
+    Forbidden(Vec<String>),
+    /// the api key is valid but its scope does not allow the endpoint
+    NotAllowed(String),
+    /// the update targets computed, read only or storyteller only ranges
+    ProtectedRange(Vec<String>),
+    /// no api key with this id exists
+    ApiKeyNotFound(String),
+    /// no proposal with this id exists
+    ProposalNotFound(String),
+    /// the request contradicts the current state, e.g. deciding a proposal twice
+    Conflict(String),
+    /// another request is writing the sheet and did not finish in time
+    SheetLocked(String),
+    /// a write under the lock of the sheet took too long and was stopped before the lock expired
+    LockTimeout(String),
+    /// the character changed since the client loaded it, holds the current etag
+    PreconditionFailed(String),
+    /// updates have to send `If-Match`
+    PreconditionRequired,
+    /// the request body is incomplete or contradicts itself
+    BadRequest(String),
+    /// the character does not have that much blood left
+    InsufficientBlood { requested: u8, available: u8 },
+    /// the character may not spend that much more blood in the current round
+    BloodPerTurnExceeded {
+        requested: u8,
+        spent_this_round: u8,
+        blood_per_turn: u8,
+    },
+    /// the body of a patch has a content type the gateway does not understand
+    UnsupportedMediaType(String),
+    /// the patch could not be applied to the character
+    InvalidPatch(String),
+    /// redis could not be reached or answered with an error
+    RedisFailure(String),
+    /// the service account could not be loaded or google rejected its token
+    GoogleAuthFailure(String),
+    /// the sheet does not exist or is not shared with the service account
+    SheetNotFound(String),
+    /// the google api quota is used up
+    QuotaExceeded,
+    /// a requested range was not part of the sheet response
+    MissingRange {
+        field_name: FieldName,
+        range: String,
+    },
+    /// a new field configuration was rejected, the old one stays active
+    InvalidConfig(String),
+    /// an A1 range could not be parsed or the values do not fit into it
+    InvalidRange(String),
+    /// the character source failed for another reason
+    SourceFailure(String),
+    /// anything else going wrong inside the gateway
+    Internal(String),
+}
+
+/// the json body sent along with an error response
+#[derive(Serialize, Debug)]
+pub struct GatewayErrorBody {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl GatewayError {
+    /// the machine readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            GatewayError::UnknownApiKey => "unknown_api_key",
+            GatewayError::Unauthorized(_) => "unauthorized",
+            GatewayError::Forbidden(_) => "forbidden",
+            GatewayError::NotAllowed(_) => "not_allowed",
+            GatewayError::ProtectedRange(_) => "protected_range",
+            GatewayError::ApiKeyNotFound(_) => "api_key_not_found",
+            GatewayError::ProposalNotFound(_) => "proposal_not_found",
+            GatewayError::Conflict(_) => "conflict",
+            GatewayError::SheetLocked(_) => "sheet_locked",
+            GatewayError::LockTimeout(_) => "lock_timeout",
+            GatewayError::PreconditionFailed(_) => "precondition_failed",
+            GatewayError::PreconditionRequired => "precondition_required",
+            GatewayError::BadRequest(_) => "bad_request",
+            GatewayError::InsufficientBlood { .. } => "insufficient_blood",
+            GatewayError::BloodPerTurnExceeded { .. } => "blood_per_turn_exceeded",
+            GatewayError::UnsupportedMediaType(_) => "unsupported_media_type",
+            GatewayError::InvalidPatch(_) => "invalid_patch",
+            GatewayError::RedisFailure(_) => "redis_failure",
+            GatewayError::GoogleAuthFailure(_) => "google_auth_failure",
+            GatewayError::SheetNotFound(_) => "sheet_not_found",
+            GatewayError::QuotaExceeded => "quota_exceeded",
+            GatewayError::MissingRange { .. } => "missing_range",
+            GatewayError::InvalidConfig(_) => "invalid_config",
+            GatewayError::InvalidRange(_) => "invalid_range",
+            GatewayError::SourceFailure(_) => "source_failure",
+            GatewayError::Internal(_) => "internal_error",
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            GatewayError::UnknownApiKey => StatusCode::UNAUTHORIZED,
+            GatewayError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            GatewayError::Forbidden(_) => StatusCode::FORBIDDEN,
+            GatewayError::NotAllowed(_) => StatusCode::FORBIDDEN,
+            GatewayError::ProtectedRange(_) => StatusCode::FORBIDDEN,
+            GatewayError::ApiKeyNotFound(_) => StatusCode::NOT_FOUND,
+            GatewayError::ProposalNotFound(_) => StatusCode::NOT_FOUND,
+            GatewayError::Conflict(_) => StatusCode::CONFLICT,
+            GatewayError::SheetLocked(_) => StatusCode::LOCKED,
+            GatewayError::LockTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            GatewayError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            GatewayError::PreconditionRequired => StatusCode::PRECONDITION_REQUIRED,
+            GatewayError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            GatewayError::InsufficientBlood { .. } => StatusCode::CONFLICT,
+            GatewayError::BloodPerTurnExceeded { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            GatewayError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            GatewayError::InvalidPatch(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            GatewayError::RedisFailure(_) => StatusCode::SERVICE_UNAVAILABLE,
+            GatewayError::GoogleAuthFailure(_) => StatusCode::BAD_GATEWAY,
+            GatewayError::SheetNotFound(_) => StatusCode::NOT_FOUND,
+            GatewayError::QuotaExceeded => StatusCode::TOO_MANY_REQUESTS,
+            GatewayError::MissingRange { .. } => StatusCode::BAD_GATEWAY,
+            GatewayError::InvalidConfig(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            GatewayError::InvalidRange(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            GatewayError::SourceFailure(_) => StatusCode::BAD_GATEWAY,
+            GatewayError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl Display for GatewayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GatewayError::UnknownApiKey => write!(f, "the api key is unknown"),
+            GatewayError::Unauthorized(reason) => write!(f, "unauthorized: {}", reason),
+            GatewayError::Forbidden(paths) => {
+                write!(f, "the api key may not change {}", paths.join(", "))
+            }
+            GatewayError::NotAllowed(reason) => write!(f, "not allowed: {}", reason),
+            GatewayError::ProtectedRange(fields) => write!(
+                f,
+                "these ranges are computed or protected and were not written: {}",
+                fields.join(", ")
+            ),
+            GatewayError::ApiKeyNotFound(key_id) => {
+                write!(f, "the api key {} does not exist", key_id)
+            }
+            GatewayError::ProposalNotFound(proposal_id) => {
+                write!(f, "the proposal {} does not exist", proposal_id)
+            }
+            GatewayError::Conflict(reason) => write!(f, "conflict: {}", reason),
+            GatewayError::SheetLocked(sheet_id) => write!(
+                f,
+                "the sheet {} is being written by another request, try again",
+                sheet_id
+            ),
+            GatewayError::LockTimeout(sheet_id) => write!(
+                f,
+                "writing the sheet {} took too long and was stopped, some cells may have been written",
+                sheet_id
+            ),
+            GatewayError::PreconditionFailed(etag) => write!(
+                f,
+                "the character changed since it was loaded, its etag is now {}",
+                etag
+            ),
+            GatewayError::PreconditionRequired => {
+                write!(
+                    f,
+                    "updates have to send the etag of the character as If-Match"
+                )
+            }
+            GatewayError::BadRequest(reason) => write!(f, "bad request: {}", reason),
+            GatewayError::InsufficientBlood {
+                requested,
+                available,
+            } => write!(
+                f,
+                "cannot spend {} blood, only {} is left",
+                requested, available
+            ),
+            GatewayError::BloodPerTurnExceeded {
+                requested,
+                spent_this_round,
+                blood_per_turn,
+            } => write!(
+                f,
+                "cannot spend {} blood in this round, {} of the {} per turn are already spent",
+                requested, spent_this_round, blood_per_turn
+            ),
+            GatewayError::UnsupportedMediaType(content_type) => write!(
+                f,
+                "patches have to be application/merge-patch+json or application/json-patch+json, not {}",
+                content_type
+            ),
+            GatewayError::InvalidPatch(reason) => write!(f, "the patch cannot be applied: {}", reason),
+            GatewayError::RedisFailure(reason) => write!(f, "redis is not available: {}", reason),
+            GatewayError::GoogleAuthFailure(reason) => {
+                write!(f, "authentication with google failed: {}", reason)
+            }
+            GatewayError::SheetNotFound(sheet_id) => write!(
+                f,
+                "the sheet {} does not exist or is not shared with the gateway",
+                sheet_id
+            ),
+            GatewayError::QuotaExceeded => {
+                write!(f, "the spreadsheet quota is exceeded, try again later")
+            }
+            GatewayError::MissingRange { field_name, range } => write!(
+                f,
+                "the range {} ({}) is missing in the sheet response",
+                range, field_name
+            ),
+            GatewayError::InvalidConfig(report) => {
+                write!(f, "the configuration was not applied: {}", report)
+            }
+            GatewayError::InvalidRange(reason) => write!(f, "invalid range: {}", reason),
+            GatewayError::SourceFailure(reason) => {
+                write!(f, "the character source failed: {}", reason)
+            }
+            GatewayError::Internal(reason) => write!(f, "internal error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for GatewayError {}
+
+impl IntoResponse for GatewayError {
+    fn into_response(self) -> Response {
+        let status_code = self.status_code();
+
+        if status_code.is_server_error() {
+            tracing::error!("{}", self);
+        } else {
+            tracing::debug!("{}", self);
+        }
+
+        let body = GatewayErrorBody {
+            code: self.code(),
+            message: self.to_string(),
+        };
+
+        (status_code, Json(body)).into_response()
+    }
+}
@@ -0,0 +1,12 @@
+use crate::character_db::health_box::DamageType;
+use serde::Deserialize;
+
+/// the body of a damage or healing request
+#[derive(Deserialize, Debug)]
+pub struct HealthChangeInput {
+    /// the number of health boxes to mark or clear
+    pub amount: u8,
+    /// `normal` unless given, healing only clears boxes of this type
+    #[serde(default)]
+    pub damage_type: DamageType,
+}
@@ -0,0 +1,89 @@
+use crate::{
+    api_key_auth::ApiKey,
+    api_key_client::ApiKeyClient,
+    api_key_scope::ApiKeyScope,
+    app_state::AppState,
+    character_db::{health_tracks::HealthTracks, player_character_client::PlayerCharacterClient},
+    gateway_error::GatewayError,
+    health_change::HealthChangeInput,
+    sheet_lock::with_sheet_lock,
+};
+use axum::{Extension, Json};
+
+/// the path of the health tracks in the character, as reported when a key may not change them
+const HEALTH_PATH: &str = "battle_information.base.health";
+
+/// marks lost health boxes, spilling over from the healthy into the injured and incapacitated track
+pub async fn character_damage(
+    api_key: ApiKey,
+    Extension(app_state): Extension<AppState>,
+    Json(input): Json<HealthChangeInput>,
+) -> Result<Json<HealthTracks>, GatewayError> {
+    change_health(api_key, app_state, |health| {
+        health.apply_damage(input.amount, input.damage_type)
+    })
+    .await
+}
+
+/// clears lost health boxes of one damage type, the last marked box heals first
+pub async fn character_heal(
+    api_key: ApiKey,
+    Extension(app_state): Extension<AppState>,
+    Json(input): Json<HealthChangeInput>,
+) -> Result<Json<HealthTracks>, GatewayError> {
+    change_health(api_key, app_state, |health| {
+        health.apply_healing(input.amount, input.damage_type)
+    })
+    .await
+}
+
+/// health changes too often during a fight to wait for a storyteller, so players write them directly
+async fn change_health<F>(
+    api_key: ApiKey,
+    app_state: AppState,
+    change: F,
+) -> Result<Json<HealthTracks>, GatewayError>
+where
+    F: FnOnce(&HealthTracks) -> HealthTracks,
+{
+    tracing::debug!("{:?}", api_key);
+
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+    let api_key_record = api_key_client.map_key(&api_key.0).await?;
+
+    if api_key_record.scope == ApiKeyScope::ReadOnly {
+        return Err(GatewayError::Forbidden(vec![HEALTH_PATH.to_string()]));
+    }
+
+    let player_character_client =
+        PlayerCharacterClient::new(app_state.character_source, app_state.layouts.load_full());
+    let sheet_id = api_key_record.sheet_id.clone();
+
+    // the marks are counted and written under the lock, so concurrent hits add up
+    let health = with_sheet_lock(app_state.redis_connection.clone(), &sheet_id, async {
+        let current = player_character_client.parse_data(sheet_id.clone()).await?;
+        let current = &current.battle_information.base.health;
+
+        if current.has_unknown_marks() {
+            return Err(GatewayError::Conflict(
+                "the health tracks hold marks which are neither normal nor aggravated damage, they are listed in the warnings of the character".to_string(),
+            ));
+        }
+
+        let health = change(current);
+
+        let updated_cells = player_character_client
+            .write_health(&sheet_id, &health, api_key_record.scope)
+            .await?;
+        tracing::debug!("{:?} changed {} health boxes", api_key, updated_cells);
+
+        Ok(health)
+    })
+    .await?;
+
+    api_key_client
+        .remove_cached_data(&api_key_record.sheet_id)
+        .await?;
+
+    Ok(Json(health))
+}
@@ -1,27 +1,147 @@
+mod admin_auth;
+mod admin_handlers;
+mod api_key_auth;
 mod api_key_client;
+mod api_key_record;
+mod api_key_scope;
+mod app_state;
+mod blood_change;
+mod blood_client;
+mod blood_handlers;
+mod blood_status;
 mod character_db;
 mod config;
+mod config_reloader;
+mod etag;
+mod gateway_error;
+mod health_change;
+mod health_handlers;
+mod proposal;
+mod proposal_client;
+mod proposal_handlers;
+mod sheet_lock;
+mod update_options;
 
 use crate::{
-    api_key_client::ApiKeyClient, character_db::player_character_client::PlayerCharacterClient,
+    admin_handlers::{
+        api_key_create, api_key_inspect, api_key_list, api_key_migrate_legacy, api_key_revoke,
+        api_key_rotate, config_reload,
+    },
+    api_key_auth::ApiKey,
+    api_key_client::ApiKeyClient,
+    api_key_scope::ApiKeyScope,
+    app_state::AppState,
+    blood_handlers::{blood_feed, blood_inspect, blood_new_round, blood_reset, blood_spend},
+    character_db::{
+        character_patch::CharacterPatch, character_update::CharacterUpdate,
+        damage_marks::DamageMarks, player_character_client::PlayerCharacterClient,
+    },
+    config::{
+        config_client::config_path,
+        sheet_layouts::{layout_directory, SheetLayouts},
+    },
+    config_reloader::watch_config,
+    etag::{character_response, check_if_match, if_match, if_none_match},
+    gateway_error::GatewayError,
+    health_handlers::{character_damage, character_heal},
+    proposal::Proposal,
+    proposal_client::ProposalClient,
+    proposal_handlers::{
+        proposal_amend, proposal_approve, proposal_inspect, proposal_list, proposal_reject,
+    },
+    sheet_lock::with_sheet_lock,
+    update_options::UpdateOptions,
+};
+use axum::{
+    body::Bytes,
+    extract::Query,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Extension, Json, Router,
 };
-use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
 use character_db::player_character::{PlayerCharacter, PlayerCharacterUpdateInput};
-use std::{env, net::SocketAddr};
+use hyper::{header::CONTENT_TYPE, HeaderMap, StatusCode};
+use std::{env, net::SocketAddr, process};
 
 #[tokio::main]
 async fn main() {
     // initialize tracing
     tracing_subscriber::fmt::init();
 
+    // a broken field config would only fail at request time, so it is checked right away
+    let config_path = config_path();
+    let layouts = match SheetLayouts::load(&config_path, &layout_directory()) {
+        Ok(layouts) => layouts,
+        Err(report) => {
+            eprintln!("{}", report);
+            process::exit(1);
+        }
+    };
+
+    // the damage marks are read once, a sheet can not tell apart marks that are empty or equal
+    match DamageMarks::from_env() {
+        Ok(damage_marks) => damage_marks.install(),
+        Err(report) => {
+            eprintln!("{}", report);
+            process::exit(1);
+        }
+    }
+
+    if env::args().any(|x| x == "--check-config") {
+        println!(
+            "{} is valid, layouts for versions: {:?}",
+            config_path,
+            layouts.versions()
+        );
+        return;
+    }
+
+    // the character source, redis and the field configs are shared by all requests
+    let app_state = AppState::from_env(layouts).await;
+
+    // changes to the field config and the layouts are picked up while running
+    watch_config(app_state.clone());
+
     // build our application with the following routes
     let app = Router::new()
         // `GET /` goes to `root`
         .route("/", get(root))
+        .route(
+            "/character",
+            get(character_data)
+                .put(character_update)
+                .patch(character_patch),
+        )
+        // deprecated, the api key belongs into the `Authorization` header
         .route(
             "/character/:sheet_key",
-            get(character_data).put(character_update),
-        );
+            get(character_data)
+                .put(character_update)
+                .patch(character_patch),
+        )
+        .route("/character/damage", post(character_damage))
+        .route("/character/heal", post(character_heal))
+        .route("/character/blood", get(blood_inspect))
+        .route("/character/blood/spend", post(blood_spend))
+        .route("/character/blood/feed", post(blood_feed))
+        .route("/character/blood/reset", post(blood_reset))
+        .route("/character/blood/round", post(blood_new_round))
+        .route("/admin/config/reload", post(config_reload))
+        .route("/admin/api-keys", get(api_key_list).post(api_key_create))
+        .route(
+            "/admin/api-keys/:key_id",
+            get(api_key_inspect).delete(api_key_revoke),
+        )
+        .route("/admin/api-keys/:key_id/rotate", post(api_key_rotate))
+        .route("/admin/legacy-keys/migrate", post(api_key_migrate_legacy))
+        .route("/proposals", get(proposal_list))
+        .route(
+            "/proposals/:proposal_id",
+            get(proposal_inspect).put(proposal_amend),
+        )
+        .route("/proposals/:proposal_id/approve", post(proposal_approve))
+        .route("/proposals/:proposal_id/reject", post(proposal_reject))
+        .layer(Extension(app_state));
 
     // run our app
     let host = env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
@@ -40,78 +160,228 @@ async fn root() -> &'static str {
     "Hello, World!"
 }
 
-/// loading the character data from the passed in sheet
+/// loading the character data from the passed in sheet, answered with `304` if `If-None-Match` matches its etag
 async fn character_data(
-    Path(api_key): Path<String>,
-) -> (StatusCode, Json<Option<PlayerCharacter>>) {
-    tracing::debug!("API Key: {:?}", api_key);
-
-    let db_connection_string =
-        env::var("DB_CONNECTION_STRING").unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
-    let mut api_key_client = ApiKeyClient::new(db_connection_string);
+    api_key: ApiKey,
+    headers: HeaderMap,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Response, GatewayError> {
+    tracing::debug!("{:?}", api_key);
 
-    let cached_character = api_key_client.get_cached_data(&api_key);
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+    let api_key_record = api_key_client.map_key(&api_key.0).await?;
 
-    if let Ok(player_character) = cached_character {
-        tracing::debug!("found cached data for api key {:?}", api_key);
-        return (StatusCode::OK, Json(Some(player_character)));
+    if let Some(player_character) = api_key_client
+        .get_cached_data(&api_key_record.sheet_id)
+        .await?
+    {
+        tracing::debug!("found cached data for {:?}", api_key);
+        return character_response(if_none_match(&headers), player_character);
     }
 
     tracing::debug!("no cached data found, retrieving from google spreadsheets");
-    let sheet_key = match api_key_client.map_key(&api_key) {
-        Ok(key) => key,
-        Err(err) => return (err, Json(None)),
-    };
-
-    let service_account_info = env::var("SERVICE_ACCOUNT_INFORMATION").unwrap_or_default();
-
-    let mut player_character_client = match PlayerCharacterClient::new(service_account_info).await {
-        Ok(client) => client,
-        Err(error_code) => return (error_code, Json(None)),
-    };
 
-    let retrieved_character = match player_character_client.parse_data(sheet_key).await {
-        Ok(data) => data,
-        Err(error_code) => return (error_code, Json(None)),
-    };
+    let player_character_client =
+        PlayerCharacterClient::new(app_state.character_source, app_state.layouts.load_full());
+    let retrieved_character = player_character_client
+        .parse_data(api_key_record.sheet_id.clone())
+        .await?;
 
-    api_key_client.write_player_character_to_cache(&api_key, &retrieved_character);
+    api_key_client
+        .write_player_character_to_cache(&api_key_record.sheet_id, &retrieved_character)
+        .await;
 
     // returning the result
-    (StatusCode::OK, Json(Some(retrieved_character)))
+    character_response(if_none_match(&headers), retrieved_character)
 }
 
+/// storytellers write straight into the sheet, players submit a proposal for a storyteller to approve
+///
+/// With `?dry_run=true` only the ranges the update would write are returned.
 async fn character_update(
-    Path(api_key): Path<String>,
+    api_key: ApiKey,
+    headers: HeaderMap,
+    Query(options): Query<UpdateOptions>,
+    Extension(app_state): Extension<AppState>,
     Json(payload): Json<PlayerCharacterUpdateInput>,
-) -> (StatusCode, Json<Option<i32>>) {
-    tracing::debug!("API Key: {:?}", api_key);
+) -> Result<Response, GatewayError> {
     tracing::debug!("Payload: {:?}", payload);
 
-    let db_connection_string =
-        env::var("DB_CONNECTION_STRING").unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
-    let mut api_key_client = ApiKeyClient::new(db_connection_string);
+    apply_update(
+        api_key,
+        headers,
+        options,
+        app_state,
+        CharacterUpdate::Replace(Box::new(payload)),
+    )
+    .await
+}
 
-    let sheet_key = match api_key_client.map_key(&api_key) {
-        Ok(key) => key,
-        Err(err) => return (err, Json(None)),
-    };
+/// like the `PUT`, but with a merge patch or json patch against the json of the character
+async fn character_patch(
+    api_key: ApiKey,
+    headers: HeaderMap,
+    Query(options): Query<UpdateOptions>,
+    Extension(app_state): Extension<AppState>,
+    body: Bytes,
+) -> Result<Response, GatewayError> {
+    let content_type = headers.get(CONTENT_TYPE).and_then(|x| x.to_str().ok());
+    let patch = CharacterPatch::parse(content_type, &body)?;
+    tracing::debug!("Patch: {:?}", patch);
+
+    apply_update(
+        api_key,
+        headers,
+        options,
+        app_state,
+        CharacterUpdate::Patch(patch),
+    )
+    .await
+}
+
+async fn apply_update(
+    api_key: ApiKey,
+    headers: HeaderMap,
+    options: UpdateOptions,
+    app_state: AppState,
+    update: CharacterUpdate,
+) -> Result<Response, GatewayError> {
+    tracing::debug!("{:?}", api_key);
 
-    let service_account_info = env::var("SERVICE_ACCOUNT_INFORMATION").unwrap_or_default();
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
 
-    let player_character_client = match PlayerCharacterClient::new(service_account_info).await {
-        Ok(client) => client,
-        Err(error_code) => return (error_code, Json(None)),
+    let api_key_record = api_key_client.map_key(&api_key.0).await?;
+    let scope = api_key_record.scope;
+
+    // a put is rejected before the sheet is read, a patch once it is resolved
+    if let CharacterUpdate::Replace(payload) = &update {
+        scope.check_update(payload)?;
+    }
+
+    let player_character_client =
+        PlayerCharacterClient::new(app_state.character_source, app_state.layouts.load_full());
+
+    if options.dry_run {
+        let current = load_current(
+            &player_character_client,
+            &api_key_record.sheet_id,
+            &headers,
+            update.needs_current(),
+        )
+        .await?;
+        let payload = update.resolve(current.as_ref(), scope)?;
+
+        let range_changes = player_character_client
+            .preview_data(api_key_record.sheet_id, payload, scope)
+            .await?;
+
+        return Ok(Json(range_changes).into_response());
+    }
+
+    if scope != ApiKeyScope::Storyteller {
+        let current = load_current(
+            &player_character_client,
+            &api_key_record.sheet_id,
+            &headers,
+            true,
+        )
+        .await?
+        .ok_or_else(|| GatewayError::Internal("the character was not read".to_string()))?;
+        let payload = update.resolve(Some(&current), scope)?;
+        // a proposal must not carry changes to ranges the key could not write itself
+        player_character_client
+            .check_data(&api_key_record.sheet_id, payload.clone(), scope)
+            .await?;
+        let proposal = Proposal::new(&api_key_record, &current, payload)?;
+
+        let mut proposal_client = ProposalClient::new(app_state.redis_connection.clone());
+        proposal_client.save(&proposal).await?;
+        tracing::debug!("{:?} submitted proposal {}", api_key, proposal.proposal_id);
+
+        return Ok((StatusCode::ACCEPTED, Json(proposal)).into_response());
+    }
+
+    // the etag check, a patch and the write happen under the lock, so no other write can slip in between
+    let sheet_id = api_key_record.sheet_id.clone();
+    let (updated_cells, refreshed) =
+        with_sheet_lock(app_state.redis_connection.clone(), &sheet_id, async {
+            let current = load_current(
+                &player_character_client,
+                &sheet_id,
+                &headers,
+                update.needs_current(),
+            )
+            .await?;
+            let payload = update.resolve(current.as_ref(), scope)?;
+
+            let updated_cells = player_character_client
+                .write_data(sheet_id.clone(), payload, scope)
+                .await?;
+
+            // read before the lock is released, so the character is the one this write produced
+            let refreshed = match options.return_character {
+                true => Some(player_character_client.parse_data(sheet_id.clone()).await?),
+                false => None,
+            };
+
+            Ok((updated_cells, refreshed))
+        })
+        .await?;
+
+    let player_character = match refreshed {
+        Some(player_character) => player_character,
+        None => {
+            api_key_client
+                .remove_cached_data(&api_key_record.sheet_id)
+                .await?;
+
+            return Ok(Json(updated_cells).into_response());
+        }
     };
 
-    let updated_cells = match player_character_client.write_data(sheet_key, payload).await {
-        Ok(data) => data,
-        Err(error_code) => return (error_code, Json(None)),
+    // the sheet recomputed its formulas, the fresh character replaces the cached one
+    tracing::debug!("{:?} updated {} cells", api_key, updated_cells);
+    api_key_client
+        .write_player_character_to_cache(&api_key_record.sheet_id, &player_character)
+        .await;
+
+    character_response(None, player_character)
+}
+
+/// checks the `If-Match` header against the sheet and returns the character read for it
+///
+/// The sheet is read again, the cache could be older than what the client has seen.
+async fn check_current(
+    player_character_client: &PlayerCharacterClient,
+    sheet_id: &str,
+    headers: &HeaderMap,
+) -> Result<Option<PlayerCharacter>, GatewayError> {
+    let if_match = match if_match(headers)? {
+        Some(if_match) => if_match,
+        None => return Ok(None),
     };
 
-    if let Err(err_code) = api_key_client.remove_cached_data(&api_key) {
-        return (err_code, Json(None));
-    }
+    let player_character = player_character_client
+        .parse_data(sheet_id.to_string())
+        .await?;
+    check_if_match(&if_match, &player_character)?;
 
-    (StatusCode::OK, Json(Some(updated_cells)))
+    Ok(Some(player_character))
+}
+
+/// the character checked against `If-Match`, read in any case if it is required
+async fn load_current(
+    player_character_client: &PlayerCharacterClient,
+    sheet_id: &str,
+    headers: &HeaderMap,
+    required: bool,
+) -> Result<Option<PlayerCharacter>, GatewayError> {
+    match check_current(player_character_client, sheet_id, headers).await? {
+        Some(player_character) => Ok(Some(player_character)),
+        None if required => player_character_client
+            .parse_data(sheet_id.to_string())
+            .await
+            .map(Some),
+        None => Ok(None),
+    }
 }
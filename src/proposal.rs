@@ -0,0 +1,107 @@
+use crate::{
+    api_key_record::{random_token, ApiKeyRecord},
+    character_db::{
+        character_diff::{diff_update, FieldChange},
+        player_character::{PlayerCharacter, PlayerCharacterUpdateInput},
+    },
+    gateway_error::GatewayError,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// A change set submitted by a player, only written to the sheet once a storyteller approved it
+///
+/// Stored as json in redis under `proposal:<proposal_id>`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Proposal {
+    pub proposal_id: String,
+    /// the api key which submitted the proposal
+    pub key_id: String,
+    pub sheet_id: String,
+    pub character_name: String,
+    pub player_name: String,
+    pub status: ProposalStatus,
+    pub changes: PlayerCharacterUpdateInput,
+    /// the changed values compared to the character at the time of the last submission or amendment
+    pub diff: Vec<FieldChange>,
+    pub created_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+    /// `admin` or the id of the storyteller key which approved or rejected the proposal
+    pub decided_by: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl Proposal {
+    /// a pending proposal for the character of the api key, an update changing nothing is rejected
+    pub fn new(
+        api_key_record: &ApiKeyRecord,
+        current: &PlayerCharacter,
+        changes: PlayerCharacterUpdateInput,
+    ) -> Result<Proposal, GatewayError> {
+        let diff = diff_update(current, &changes)?;
+
+        if diff.is_empty() {
+            return Err(GatewayError::BadRequest(
+                "the update does not change the character".to_string(),
+            ));
+        }
+
+        Ok(Proposal {
+            proposal_id: random_token(8),
+            key_id: api_key_record.key_id.clone(),
+            sheet_id: api_key_record.sheet_id.clone(),
+            character_name: current.character_name.clone(),
+            player_name: current.player_name.clone(),
+            status: ProposalStatus::Pending,
+            changes,
+            diff,
+            created_at: Utc::now(),
+            decided_at: None,
+            decided_by: None,
+            comment: None,
+        })
+    }
+
+    /// replaces the changes of a pending proposal, the diff is taken against the current character
+    pub fn amend(
+        &mut self,
+        current: &PlayerCharacter,
+        changes: PlayerCharacterUpdateInput,
+    ) -> Result<(), GatewayError> {
+        self.diff = diff_update(current, &changes)?;
+        self.changes = changes;
+
+        Ok(())
+    }
+
+    pub fn decide(&mut self, status: ProposalStatus, reviewer: String, comment: Option<String>) {
+        self.status = status;
+        self.decided_at = Some(Utc::now());
+        self.decided_by = Some(reviewer);
+        self.comment = comment;
+    }
+
+    pub fn redis_key(proposal_id: &str) -> String {
+        format!("proposal:{}", proposal_id)
+    }
+}
+
+/// the filter of the proposal list
+#[derive(Deserialize, Debug)]
+pub struct ProposalListQuery {
+    pub status: Option<ProposalStatus>,
+}
+
+/// the optional body when approving or rejecting a proposal
+#[derive(Deserialize, Debug, Default)]
+pub struct ProposalDecisionInput {
+    pub comment: Option<String>,
+}
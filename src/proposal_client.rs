@@ -0,0 +1,106 @@
+use crate::{
+    gateway_error::GatewayError,
+    proposal::{Proposal, ProposalStatus},
+};
+use redis::{aio::ConnectionManager, AsyncCommands};
+
+pub struct ProposalClient {
+    connection: ConnectionManager,
+}
+
+impl ProposalClient {
+    pub fn new(connection: ConnectionManager) -> ProposalClient {
+        ProposalClient { connection }
+    }
+
+    pub async fn save(&mut self, proposal: &Proposal) -> Result<(), GatewayError> {
+        let serialized_proposal = serde_json::to_string(proposal)
+            .map_err(|err| GatewayError::Internal(err.to_string()))?;
+
+        self.connection
+            .set::<String, String, ()>(
+                Proposal::redis_key(&proposal.proposal_id),
+                serialized_proposal,
+            )
+            .await
+            .map_err(|err| GatewayError::RedisFailure(err.to_string()))
+    }
+
+    /// the proposal, with a `sheet_id` those of other sheets are not found
+    pub async fn get(
+        &mut self,
+        proposal_id: &str,
+        sheet_id: Option<&str>,
+    ) -> Result<Proposal, GatewayError> {
+        let value = self
+            .connection
+            .get::<String, Option<String>>(Proposal::redis_key(proposal_id))
+            .await
+            .map_err(|err| GatewayError::RedisFailure(err.to_string()))?
+            .ok_or_else(|| GatewayError::ProposalNotFound(proposal_id.to_string()))?;
+
+        let proposal = serde_json::from_str::<Proposal>(&value)
+            .map_err(|err| GatewayError::Internal(format!("unreadable proposal: {}", err)))?;
+
+        match sheet_id.is_none_or(|x| x == proposal.sheet_id) {
+            true => Ok(proposal),
+            false => Err(GatewayError::ProposalNotFound(proposal_id.to_string())),
+        }
+    }
+
+    /// the same as `get`, but only for proposals still waiting for a decision
+    pub async fn get_pending(
+        &mut self,
+        proposal_id: &str,
+        sheet_id: Option<&str>,
+    ) -> Result<Proposal, GatewayError> {
+        let proposal = self.get(proposal_id, sheet_id).await?;
+
+        match proposal.status {
+            ProposalStatus::Pending => Ok(proposal),
+            status => Err(GatewayError::Conflict(format!(
+                "the proposal {} is already {:?}",
+                proposal_id, status
+            ))),
+        }
+    }
+
+    /// all proposals, optionally only those with the given status or of one sheet, oldest first
+    pub async fn list(
+        &mut self,
+        status: Option<ProposalStatus>,
+        sheet_id: Option<&str>,
+    ) -> Result<Vec<Proposal>, GatewayError> {
+        let mut redis_keys: Vec<String> = Vec::new();
+        {
+            let mut iter = self
+                .connection
+                .scan_match::<&str, String>("proposal:*")
+                .await
+                .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+            while let Some(redis_key) = iter.next_item().await {
+                redis_keys.push(redis_key);
+            }
+        }
+
+        let mut proposals: Vec<Proposal> = Vec::new();
+
+        for redis_key in redis_keys {
+            let proposal = self
+                .get(redis_key.trim_start_matches("proposal:"), sheet_id)
+                .await;
+
+            match proposal {
+                Ok(proposal) if status.is_none_or(|x| x == proposal.status) => {
+                    proposals.push(proposal)
+                }
+                Ok(_) | Err(GatewayError::ProposalNotFound(_)) => {}
+                Err(err) => tracing::warn!("skipping proposal {}: {}", redis_key, err),
+            }
+        }
+        proposals.sort_by_key(|x| x.created_at);
+
+        Ok(proposals)
+    }
+}
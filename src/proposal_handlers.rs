@@ -0,0 +1,164 @@
+use crate::{
+    api_key_auth::StorytellerAuth,
+    api_key_client::ApiKeyClient,
+    api_key_scope::ApiKeyScope,
+    app_state::AppState,
+    character_db::{
+        player_character::PlayerCharacterUpdateInput,
+        player_character_client::PlayerCharacterClient,
+    },
+    gateway_error::GatewayError,
+    proposal::{Proposal, ProposalDecisionInput, ProposalListQuery, ProposalStatus},
+    proposal_client::ProposalClient,
+    sheet_lock::with_sheet_lock,
+};
+use axum::{
+    extract::{Path, Query},
+    Extension, Json,
+};
+
+/// the proposals, `?status=pending` lists only those waiting for a decision
+pub async fn proposal_list(
+    storyteller: StorytellerAuth,
+    Query(query): Query<ProposalListQuery>,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Json<Vec<Proposal>>, GatewayError> {
+    let mut proposal_client = ProposalClient::new(app_state.redis_connection.clone());
+
+    Ok(Json(
+        proposal_client
+            .list(query.status, storyteller.sheet_id.as_deref())
+            .await?,
+    ))
+}
+
+pub async fn proposal_inspect(
+    storyteller: StorytellerAuth,
+    Path(proposal_id): Path<String>,
+    Extension(app_state): Extension<AppState>,
+) -> Result<Json<Proposal>, GatewayError> {
+    let mut proposal_client = ProposalClient::new(app_state.redis_connection.clone());
+
+    Ok(Json(
+        proposal_client
+            .get(&proposal_id, storyteller.sheet_id.as_deref())
+            .await?,
+    ))
+}
+
+/// replaces the changes of a pending proposal
+pub async fn proposal_amend(
+    storyteller: StorytellerAuth,
+    Path(proposal_id): Path<String>,
+    Extension(app_state): Extension<AppState>,
+    Json(payload): Json<PlayerCharacterUpdateInput>,
+) -> Result<Json<Proposal>, GatewayError> {
+    let mut proposal_client = ProposalClient::new(app_state.redis_connection.clone());
+    let sheet_id = proposal_client
+        .get(&proposal_id, storyteller.sheet_id.as_deref())
+        .await?
+        .sheet_id;
+
+    let player_character_client =
+        PlayerCharacterClient::new(app_state.character_source, app_state.layouts.load_full());
+
+    // under the lock, so an approval can not write the changes this amend replaces
+    let proposal = with_sheet_lock(app_state.redis_connection.clone(), &sheet_id, async {
+        let mut proposal = proposal_client
+            .get_pending(&proposal_id, Some(&sheet_id))
+            .await?;
+        let current = player_character_client.parse_data(sheet_id.clone()).await?;
+
+        proposal.amend(&current, payload)?;
+        proposal_client.save(&proposal).await?;
+
+        Ok(proposal)
+    })
+    .await?;
+
+    Ok(Json(proposal))
+}
+
+/// writes the changes of a pending proposal to the sheet
+pub async fn proposal_approve(
+    storyteller: StorytellerAuth,
+    Path(proposal_id): Path<String>,
+    Extension(app_state): Extension<AppState>,
+    decision: Option<Json<ProposalDecisionInput>>,
+) -> Result<Json<Proposal>, GatewayError> {
+    let mut proposal_client = ProposalClient::new(app_state.redis_connection.clone());
+    let sheet_id = proposal_client
+        .get(&proposal_id, storyteller.sheet_id.as_deref())
+        .await?
+        .sheet_id;
+
+    let player_character_client =
+        PlayerCharacterClient::new(app_state.character_source, app_state.layouts.load_full());
+    let Json(decision) = decision.unwrap_or_default();
+
+    // the proposal is read again under the lock, so concurrent decisions write it only once
+    let proposal = with_sheet_lock(app_state.redis_connection.clone(), &sheet_id, async {
+        let mut proposal = proposal_client
+            .get_pending(&proposal_id, Some(&sheet_id))
+            .await?;
+
+        // a storyteller decided, so the changes are written with storyteller rights
+        let updated_cells = player_character_client
+            .write_data(
+                sheet_id.clone(),
+                proposal.changes.clone(),
+                ApiKeyScope::Storyteller,
+            )
+            .await?;
+        tracing::debug!("proposal {} updated {} cells", proposal_id, updated_cells);
+
+        proposal.decide(
+            ProposalStatus::Approved,
+            storyteller.reviewer,
+            decision.comment,
+        );
+        proposal_client.save(&proposal).await?;
+
+        Ok(proposal)
+    })
+    .await?;
+
+    let mut api_key_client = ApiKeyClient::new(app_state.redis_connection.clone());
+    api_key_client
+        .remove_cached_data(&proposal.sheet_id)
+        .await?;
+
+    Ok(Json(proposal))
+}
+
+pub async fn proposal_reject(
+    storyteller: StorytellerAuth,
+    Path(proposal_id): Path<String>,
+    Extension(app_state): Extension<AppState>,
+    decision: Option<Json<ProposalDecisionInput>>,
+) -> Result<Json<Proposal>, GatewayError> {
+    let mut proposal_client = ProposalClient::new(app_state.redis_connection.clone());
+    let sheet_id = proposal_client
+        .get(&proposal_id, storyteller.sheet_id.as_deref())
+        .await?
+        .sheet_id;
+    let Json(decision) = decision.unwrap_or_default();
+
+    let proposal = with_sheet_lock(app_state.redis_connection.clone(), &sheet_id, async {
+        let mut proposal = proposal_client
+            .get_pending(&proposal_id, Some(&sheet_id))
+            .await?;
+
+        proposal.decide(
+            ProposalStatus::Rejected,
+            storyteller.reviewer,
+            decision.comment,
+        );
+        proposal_client.save(&proposal).await?;
+
+        Ok(proposal)
+    })
+    .await?;
+
+    Ok(Json(proposal))
+}
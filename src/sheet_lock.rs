@@ -0,0 +1,131 @@
+use crate::{api_key_record::random_token, gateway_error::GatewayError};
+use redis::aio::ConnectionManager;
+use std::{
+    env,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+/// how often a waiting request tries to get the lock
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// releases the lock only if it still belongs to the holder, an expired lock may have a new one
+const RELEASE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+/// A lock of a single sheet held in redis, so writes of several requests or gateways do not interleave
+///
+/// The lock expires after `LOCK_TTL_MS` (default 30000) in case its holder dies, a request waits
+/// up to `LOCK_WAIT_MS` (default 5000) for it.
+pub struct SheetLock {
+    connection: ConnectionManager,
+    redis_key: String,
+    token: String,
+    ttl: Duration,
+}
+
+impl SheetLock {
+    pub fn redis_key(sheet_id: &str) -> String {
+        format!("lock:{}", sheet_id)
+    }
+
+    /// waits for the lock of the sheet, `GatewayError::SheetLocked` if it stays taken
+    pub async fn acquire(
+        mut connection: ConnectionManager,
+        sheet_id: &str,
+    ) -> Result<SheetLock, GatewayError> {
+        let redis_key = SheetLock::redis_key(sheet_id);
+        let token = random_token(16);
+        let ttl = env_duration("LOCK_TTL_MS", 30000);
+        let wait = env_duration("LOCK_WAIT_MS", 5000);
+        let started = Instant::now();
+
+        loop {
+            let acquired = redis::cmd("SET")
+                .arg(&redis_key)
+                .arg(&token)
+                .arg("NX")
+                .arg("PX")
+                .arg(ttl.as_millis() as u64)
+                .query_async::<_, Option<String>>(&mut connection)
+                .await
+                .map_err(|err| GatewayError::RedisFailure(err.to_string()))?;
+
+            if acquired.is_some() {
+                return Ok(SheetLock {
+                    connection,
+                    redis_key,
+                    token,
+                    ttl,
+                });
+            }
+
+            if started.elapsed() >= wait {
+                return Err(GatewayError::SheetLocked(sheet_id.to_string()));
+            }
+
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    }
+
+    /// how long the holder may work with the lock, leaving a margin before it expires
+    pub fn time_limit(&self) -> Duration {
+        self.ttl * 4 / 5
+    }
+
+    /// a failed release only delays the next writer until the lock expires
+    pub async fn release(mut self) {
+        let result = redis::Script::new(RELEASE_SCRIPT)
+            .key(&self.redis_key)
+            .arg(&self.token)
+            .invoke_async::<_, i32>(&mut self.connection)
+            .await;
+
+        if let Err(err) = result {
+            tracing::warn!("could not release {}: {:?}", self.redis_key, err);
+        }
+    }
+}
+
+/// runs the operation while holding the lock of the sheet, the lock is released on errors as well
+///
+/// An operation running longer than `SheetLock::time_limit` is stopped, so it never writes without
+/// the lock. Its lock is left to expire, a request it sent last may still reach the sheet.
+pub async fn with_sheet_lock<T, F>(
+    connection: ConnectionManager,
+    sheet_id: &str,
+    operation: F,
+) -> Result<T, GatewayError>
+where
+    F: Future<Output = Result<T, GatewayError>>,
+{
+    let lock = SheetLock::acquire(connection, sheet_id).await?;
+
+    match tokio::time::timeout(lock.time_limit(), operation).await {
+        Ok(result) => {
+            lock.release().await;
+            result
+        }
+        Err(_) => {
+            tracing::warn!(
+                "the write of {} was stopped after {:?}",
+                sheet_id,
+                lock.time_limit()
+            );
+            Err(GatewayError::LockTimeout(sheet_id.to_string()))
+        }
+    }
+}
+
+fn env_duration(name: &str, default_millis: u64) -> Duration {
+    let millis = env::var(name)
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(default_millis);
+
+    Duration::from_millis(millis)
+}
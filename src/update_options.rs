@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// the query parameters of the character update
+#[derive(Deserialize, Debug, Default)]
+pub struct UpdateOptions {
+    /// only report the ranges the update would write, with their current and new values
+    #[serde(default)]
+    pub dry_run: bool,
+    /// read the sheet again after writing and return the whole character instead of the number of cells
+    #[serde(default)]
+    pub return_character: bool,
+}